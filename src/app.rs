@@ -1,13 +1,47 @@
-use std::{cmp::min, process::Command};
+use std::{
+    cmp::min,
+    collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use ratatui::widgets::ListState;
 use syntect::{
     highlighting::{Theme, ThemeSet},
     parsing::SyntaxSet,
 };
 
-use crate::diff_file::{DiffFile, DiffLine};
+use crate::{
+    clipboard::{Copied, copy},
+    comments::{Comment, Comments},
+    commit_log::{CommitDetails, CommitLog},
+    commit_steps::CommitSteps,
+    conflicts::{ConflictFile, ConflictRegion, Resolution},
+    diff_file::{DiffFile, DiffLine, LineType},
+    diff_source::DiffSource,
+    git,
+    patch::{file_patch, format_patch, hunk_at, hunk_patch, hunk_ranges, patch_file_name},
+    review::Review,
+    status::{Status, toggle_hunk},
+    untracked::load_untracked,
+    viewed::ViewedFiles,
+};
+
+/// Number of commits from the end of the loaded log at which the next page is fetched.
+const LOG_PREFETCH_DISTANCE: usize = 20;
+
+/// Files that review comments are exported to.
+const COMMENTS_MARKDOWN_FILE: &str = "review-comments.md";
+const COMMENTS_JSON_FILE: &str = "review-comments.json";
+
+/// File that selected hunks are exported to as a plain patch.
+const SELECTED_HUNKS_FILE: &str = "selected-hunks.patch";
+
+/// How long a transient status message, such as a copy confirmation, stays up.
+const FLASH_DURATION: Duration = Duration::from_secs(3);
 
 pub enum Action {
     Quit,
@@ -21,6 +55,58 @@ pub enum Action {
     Bottom,
     ScrollLeft { amount: usize },
     ScrollRight { amount: usize },
+    NextCommit { amount: usize },
+    PrevCommit { amount: usize },
+    FirstCommit,
+    LastCommit,
+    OpenCommit,
+    ShowLog,
+    ToggleCommitHeader,
+    NextStep,
+    PrevStep,
+    ShowRangeDiff,
+    ToggleViewed,
+    NextUnviewed,
+    ToggleUntracked,
+    ToggleStaged,
+    Reload,
+    StartYank,
+    Yank { target: YankTarget },
+    CancelYank,
+    ToggleLineMark,
+    ToggleHunkSelection,
+    ExportHunks { message: Option<String> },
+    StartComment,
+    CommentChar { c: char },
+    CommentBackspace,
+    SaveComment,
+    CancelComment,
+    ExportComments,
+    NextConflictFile,
+    PrevConflictFile,
+    NextConflict,
+    PrevConflict,
+    Resolve { resolution: Option<Resolution> },
+    WriteResolved,
+}
+
+/// What a yank copies to the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YankTarget {
+    /// The selected file's path.
+    Path,
+    /// The hunk at the top of the diff pane, as a patch.
+    Hunk,
+    /// The content of the lines from the line mark to the top of the diff pane.
+    Lines,
+}
+
+/// Which screen currently fills the content area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum View {
+    Diff,
+    Log,
+    Conflicts,
 }
 
 pub struct App {
@@ -34,6 +120,42 @@ pub struct App {
     pub syntax_set: SyntaxSet,
     pub theme_set: ThemeSet,
     pub running: bool,
+    pub view: View,
+    pub commit_log: Option<CommitLog>,
+    pub selected_commit: usize,
+    pub commit_list_state: ListState,
+    pub status_message: Option<String>,
+    /// When the status message disappears, for transient messages.
+    status_expires_at: Option<Instant>,
+    pub commit_details: Option<CommitDetails>,
+    pub expand_commit_header: bool,
+    pub commit_steps: Option<CommitSteps>,
+    pub review: Option<Review>,
+    pub status: Option<Status>,
+    /// Where the files were loaded from, for reloading.
+    source: Option<DiffSource>,
+    pub viewed: ViewedFiles,
+    /// Untracked files found in working-tree mode, listed after the tracked changes.
+    pub untracked: Vec<DiffFile>,
+    pub show_untracked: bool,
+    /// Whether to look for untracked files at all, which only the interactive TUI does so
+    /// that exported output matches `git diff`.
+    pub find_untracked: bool,
+    pub comments: Comments,
+    /// The comment being typed, while in comment input mode.
+    pub comment_draft: Option<String>,
+    /// Set after `y` while waiting for the key naming what to copy.
+    pub yank_pending: bool,
+    /// The file and line index where a line selection for yanking starts.
+    pub line_mark: Option<(usize, usize)>,
+    /// Hunks selected for export, by section (staged or not, in status mode), file name and
+    /// `@@` header.
+    pub selected_hunks: HashSet<(bool, String, String)>,
+    pub conflicts: Vec<ConflictFile>,
+    pub selected_conflict_file: usize,
+    pub conflict_file_state: ListState,
+    /// Index of the selected conflict region within the selected conflict file.
+    pub selected_region: usize,
 }
 
 impl App {
@@ -52,31 +174,216 @@ impl App {
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme_set: ThemeSet::load_defaults(),
             running: true,
+            view: View::Diff,
+            commit_log: None,
+            selected_commit: 0,
+            commit_list_state: ListState::default(),
+            status_message: None,
+            status_expires_at: None,
+            commit_details: None,
+            expand_commit_header: true,
+            commit_steps: None,
+            review: None,
+            status: None,
+            source: None,
+            viewed: ViewedFiles::default(),
+            untracked: Vec::new(),
+            show_untracked: true,
+            find_untracked: false,
+            comments: Comments::default(),
+            comment_draft: None,
+            yank_pending: false,
+            line_mark: None,
+            selected_hunks: HashSet::new(),
+            conflicts: Vec::new(),
+            selected_conflict_file: 0,
+            conflict_file_state: ListState::default(),
+            selected_region: 0,
         }
     }
 
-    pub fn load_diff(&mut self, args: &str) -> Result<()> {
-        let mut cmd = Command::new("git");
-        cmd.arg("diff").arg("--no-prefix");
+    pub fn load_diff(&mut self, source: &DiffSource) -> Result<()> {
+        self.load_files(source)?;
+        self.load_state(source);
 
-        if !args.is_empty() {
-            for arg in args.split_whitespace() {
-                cmd.arg(arg);
-            }
+        Ok(())
+    }
+
+    /// Loads the files of `source`, followed by the untracked files when it shows the
+    /// working tree.
+    fn load_files(&mut self, source: &DiffSource) -> Result<()> {
+        self.files = source.load_files()?;
+        self.source = Some(source.clone());
+        self.untracked = match source.working_tree_paths() {
+            Some(paths) if self.find_untracked => load_untracked(&paths)?,
+            _ => Vec::new(),
+        };
+        if self.show_untracked {
+            self.files.extend(self.untracked.iter().cloned());
         }
+        self.viewed.set_files(&self.files);
 
-        let output = cmd.output()?;
-        let diff_text = String::from_utf8_lossy(&output.stdout);
+        Ok(())
+    }
 
-        self.files = parse_diff(&diff_text);
+    /// Loads the viewed marks and comments saved for a newly loaded `source`, and selects
+    /// its first file.
+    fn load_state(&mut self, source: &DiffSource) {
+        self.viewed = ViewedFiles::load(source).unwrap_or_default();
+        self.viewed.set_files(&self.files);
+        // Without a save location a bad comments file is left alone rather than overwritten
+        self.comments = Comments::load(source).unwrap_or_else(|err| {
+            self.show_message(format!("{err:#}"));
+            Comments::default()
+        });
+        self.selected_file = 0;
+        self.scroll_offset = 0;
+        self.horizontal_scroll_offset = 0;
 
         if !self.files.is_empty() {
             self.file_list_state.select(Some(0));
         }
+    }
+
+    /// Loads the diff of a single commit along with its metadata.
+    pub fn load_commit(&mut self, rev: &str) -> Result<()> {
+        self.load_diff(&DiffSource::for_commit(rev, &[])?)?;
+        self.commit_details = Some(CommitDetails::load(rev)?);
+
+        Ok(())
+    }
+
+    /// Loads the staged and unstaged changes into one file list, staged first.
+    pub fn load_status(&mut self, status: Status) -> Result<()> {
+        let source = status.unstaged_source();
+        self.load_status_files(status)?;
+        self.load_state(&source);
+
+        Ok(())
+    }
+
+    fn load_status_files(&mut self, mut status: Status) -> Result<()> {
+        let staged = status.staged_source().load_files()?;
+        self.load_files(&status.unstaged_source())?;
+        status.staged_count = staged.len();
+        self.files.splice(0..0, staged);
+        self.viewed.set_files(&self.files);
+        self.status = Some(status);
+
+        Ok(())
+    }
+
+    /// Stages the hunk at the top of the diff pane, or unstages it when the file is staged,
+    /// then reloads both sections.
+    fn toggle_staged(&mut self) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let staged = self.is_staged(self.selected_file);
+
+        let result = toggle_hunk(file, self.scroll_offset, staged);
+        if let Err(err) = result.and_then(|()| self.reload()) {
+            self.show_message(format!("{err:#}"));
+        }
+    }
+
+    fn is_staged(&self, index: usize) -> bool {
+        self.status
+            .as_ref()
+            .is_some_and(|status| status.is_staged(index))
+    }
+
+    /// Reloads the current diff, keeping the selected file (matched by name, and in status
+    /// mode by section), the scroll position, and the comments and viewed marks in memory.
+    pub fn reload(&mut self) -> Result<()> {
+        let previous = self.selected_file;
+        let selected = self
+            .files
+            .get(previous)
+            .map(|file| (file.get_name().to_string(), self.is_staged(previous)));
+        let scroll_offset = self.scroll_offset;
+        let horizontal_scroll_offset = self.horizontal_scroll_offset;
+        // The marked line index may point at different lines once the files change
+        self.line_mark = None;
+
+        if let Some(status) = self.status.clone() {
+            self.load_status_files(status)?;
+        } else if let Some(source) = self.source.clone() {
+            self.load_files(&source)?;
+        }
+
+        let index = selected.and_then(|(name, staged)| {
+            self.files.iter().enumerate().position(|(index, file)| {
+                file.get_name() == name && self.is_staged(index) == staged
+            })
+        });
+        match index {
+            Some(index) => {
+                self.select_file(index);
+                self.scroll_offset =
+                    scroll_offset.min(self.files[index].line_count().saturating_sub(1));
+                self.horizontal_scroll_offset = horizontal_scroll_offset;
+            }
+            None if !self.files.is_empty() => {
+                self.select_file(previous.min(self.files.len() - 1));
+            }
+            None => {
+                self.selected_file = 0;
+                self.scroll_offset = 0;
+                self.horizontal_scroll_offset = 0;
+            }
+        }
 
         Ok(())
     }
 
+    /// Starts stepping through the commits of a range, beginning with the first commit.
+    pub fn start_steps(&mut self, steps: CommitSteps) -> Result<()> {
+        let position = if steps.commits.is_empty() { 0 } else { 1 };
+        self.commit_steps = Some(steps);
+        self.load_step(position)
+    }
+
+    /// Loads the diff for a position of the commit steps, where 0 is the whole range.
+    fn load_step(&mut self, position: usize) -> Result<()> {
+        let Some(steps) = self.commit_steps.as_mut() else {
+            return Ok(());
+        };
+        steps.position = position.min(steps.commits.len());
+        let source = steps.current_source()?;
+        let commit = steps.current_commit().map(str::to_string);
+
+        self.load_diff(&source)?;
+        self.commit_details = match commit {
+            Some(commit) => Some(CommitDetails::load(&commit)?),
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    fn step_to(&mut self, position: usize) {
+        match self.load_step(position) {
+            Ok(()) => self.status_message = None,
+            Err(err) => self.show_message(format!("{err:#}")),
+        }
+    }
+
+    /// Switches to the commit log browser.
+    pub fn show_log(&mut self, log: CommitLog) {
+        self.commit_log = Some(log);
+        self.selected_commit = 0;
+        self.commit_list_state.select(Some(0));
+        self.view = View::Log;
+    }
+
+    /// Switches to the merge conflict resolver.
+    pub fn show_conflicts(&mut self, conflicts: Vec<ConflictFile>) {
+        self.conflicts = conflicts;
+        self.view = View::Conflicts;
+        self.select_conflict_file(0);
+    }
+
     pub fn perform_action(&mut self, action: Action) {
         match action {
             Action::Quit => self.quit(),
@@ -90,6 +397,53 @@ impl App {
             Action::Bottom => self.scroll_to_bottom(),
             Action::ScrollLeft { amount } => self.scroll_left(amount),
             Action::ScrollRight { amount } => self.scroll_right(amount),
+            Action::NextCommit { amount } => self.next_commit(amount),
+            Action::PrevCommit { amount } => self.previous_commit(amount),
+            Action::FirstCommit => self.select_commit(0),
+            Action::LastCommit => self.last_commit(),
+            Action::OpenCommit => self.open_commit(),
+            Action::ShowLog => self.return_to_log(),
+            Action::ToggleCommitHeader => self.toggle_commit_header(),
+            Action::NextStep => self.next_step(),
+            Action::PrevStep => self.previous_step(),
+            Action::ShowRangeDiff => self.step_to(0),
+            Action::ToggleViewed => self.toggle_viewed(),
+            Action::NextUnviewed => self.next_unviewed(),
+            Action::ToggleUntracked => self.toggle_untracked(),
+            Action::ToggleStaged => self.toggle_staged(),
+            Action::Reload => {
+                if let Err(err) = self.reload() {
+                    self.show_message(format!("{err:#}"));
+                }
+            }
+            Action::StartYank => self.yank_pending = true,
+            Action::Yank { target } => self.yank(target),
+            Action::CancelYank => self.yank_pending = false,
+            Action::ToggleLineMark => self.toggle_line_mark(),
+            Action::ToggleHunkSelection => self.toggle_hunk_selection(),
+            Action::ExportHunks { message } => self.export_hunks(message.as_deref()),
+            Action::StartComment => self.start_comment(),
+            Action::CommentChar { c } => {
+                if let Some(draft) = self.comment_draft.as_mut() {
+                    draft.push(c);
+                }
+            }
+            Action::CommentBackspace => {
+                if let Some(draft) = self.comment_draft.as_mut() {
+                    draft.pop();
+                }
+            }
+            Action::SaveComment => self.save_comment(),
+            Action::CancelComment => self.comment_draft = None,
+            Action::ExportComments => self.export_comments(),
+            Action::NextConflictFile => self.select_conflict_file(self.selected_conflict_file + 1),
+            Action::PrevConflictFile => {
+                self.select_conflict_file(self.selected_conflict_file.saturating_sub(1))
+            }
+            Action::NextConflict => self.select_region(self.selected_region + 1),
+            Action::PrevConflict => self.select_region(self.selected_region.saturating_sub(1)),
+            Action::Resolve { resolution } => self.resolve_region(resolution),
+            Action::WriteResolved => self.write_resolved(),
         }
     }
 
@@ -119,6 +473,311 @@ impl App {
         }
     }
 
+    fn select_file(&mut self, index: usize) {
+        self.selected_file = index;
+        self.file_list_state.select(Some(index));
+        self.scroll_offset = 0;
+        self.horizontal_scroll_offset = 0;
+    }
+
+    fn toggle_viewed(&mut self) {
+        if let Err(err) = self.viewed.toggle(self.selected_file) {
+            self.show_message(format!("{err:#}"));
+        }
+    }
+
+    /// Selects the next file without a viewed mark, wrapping around to the top.
+    fn next_unviewed(&mut self) {
+        let count = self.files.len();
+        let next = (1..=count)
+            .map(|offset| (self.selected_file + offset) % count)
+            .find(|&index| !self.viewed.is_viewed(index));
+
+        match next {
+            Some(index) => self.select_file(index),
+            None => self.show_message("All files viewed".to_string()),
+        }
+    }
+
+    /// Shows or hides the untracked files at the end of the file list.
+    fn toggle_untracked(&mut self) {
+        if self.untracked.is_empty() {
+            self.show_message("No untracked files".to_string());
+            return;
+        }
+
+        self.show_untracked = !self.show_untracked;
+        if self.show_untracked {
+            self.files.extend(self.untracked.iter().cloned());
+        } else {
+            self.files
+                .truncate(self.files.len().saturating_sub(self.untracked.len()));
+            if self.selected_file >= self.files.len() {
+                self.select_file(self.files.len().saturating_sub(1));
+            }
+        }
+        self.viewed.set_files(&self.files);
+    }
+
+    /// Returns the selected file and its first numbered line at the top of the diff pane,
+    /// which is where comments are attached.
+    pub fn current_line(&self) -> Option<(&DiffFile, &DiffLine)> {
+        let file = self.files.get(self.selected_file)?;
+        let line = file
+            .lines
+            .iter()
+            .skip(self.scroll_offset)
+            .find(|line| line.old_line_num.is_some() || line.new_line_num.is_some())?;
+
+        Some((file, line))
+    }
+
+    /// Returns the selected file's path on disk and the line to open it at in an editor: the
+    /// first line at or after the top of the diff pane that exists in the new version.
+    pub fn editor_target(&self) -> Result<(PathBuf, u32)> {
+        let Some(file) = self.files.get(self.selected_file) else {
+            bail!("no file selected");
+        };
+        if file.get_status() == 'D' {
+            bail!("{} was deleted", file.get_name());
+        }
+
+        let line = file
+            .lines
+            .iter()
+            .skip(self.scroll_offset)
+            .find_map(|line| line.new_line_num)
+            .or_else(|| file.lines.iter().rev().find_map(|line| line.new_line_num))
+            .unwrap_or(1);
+
+        let Some(source) = &self.source else {
+            bail!("{} isn't a file on disk", file.get_name());
+        };
+        Ok((source.file_path(file.get_name())?, line))
+    }
+
+    /// Shows a status message that stays until it is replaced or dismissed.
+    pub fn show_message(&mut self, message: String) {
+        self.status_message = Some(message);
+        self.status_expires_at = None;
+    }
+
+    /// Shows a status message that clears itself after a few seconds.
+    fn flash(&mut self, message: String) {
+        self.status_message = Some(message);
+        self.status_expires_at = Some(Instant::now() + FLASH_DURATION);
+    }
+
+    /// Returns how long until a transient status message should be cleared.
+    pub fn status_time_left(&self) -> Option<Duration> {
+        self.status_expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Clears a transient status message once it has been shown long enough.
+    pub fn expire_status_message(&mut self) {
+        if self.status_time_left() == Some(Duration::ZERO) {
+            self.status_message = None;
+            self.status_expires_at = None;
+        }
+    }
+
+    /// Marks the line at the top of the diff pane as the start of a line selection, or
+    /// clears the mark.
+    fn toggle_line_mark(&mut self) {
+        if self.line_mark.take().is_some() {
+            self.flash("Line mark cleared".to_string());
+        } else if self.current_line().is_some() {
+            self.line_mark = Some((self.selected_file, self.scroll_offset));
+            self.flash("Line marked; scroll to the last line and press y l to copy".to_string());
+        }
+    }
+
+    /// Returns the content of the lines from the line mark to the top of the diff pane, or of
+    /// just the top line without a mark in this file.
+    fn marked_lines(&self) -> Vec<&str> {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return Vec::new();
+        };
+        let Some(last) = file.lines.len().checked_sub(1) else {
+            return Vec::new();
+        };
+
+        let current = self.scroll_offset.min(last);
+        let (start, end) = match self.line_mark {
+            Some((index, mark)) if index == self.selected_file => {
+                (mark.min(current), mark.max(current).min(last))
+            }
+            _ => (current, current),
+        };
+
+        file.lines[start..=end]
+            .iter()
+            .filter(|line| line.line_type != LineType::Header)
+            .map(|line| line.content.as_str())
+            .collect()
+    }
+
+    /// Copies the selected file's path, the current hunk or the marked lines.
+    fn yank(&mut self, target: YankTarget) {
+        self.yank_pending = false;
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+
+        let (what, text) = match target {
+            YankTarget::Path => ("path".to_string(), Some(file.get_name().to_string())),
+            YankTarget::Hunk => ("hunk".to_string(), hunk_patch(file, self.scroll_offset)),
+            YankTarget::Lines => {
+                let lines = self.marked_lines();
+                (
+                    format!("{} lines", lines.len()),
+                    (!lines.is_empty()).then(|| lines.join("\n") + "\n"),
+                )
+            }
+        };
+        let Some(text) = text else {
+            self.flash(format!("No {what} to copy"));
+            return;
+        };
+        if target == YankTarget::Lines {
+            self.line_mark = None;
+        }
+
+        match copy(&text) {
+            Ok(Copied::Clipboard) => self.flash(format!("Copied {what} to the clipboard")),
+            Ok(Copied::File(path)) => self.flash(format!("Wrote {what} to {}", path.display())),
+            Err(err) => self.show_message(format!("{err:#}")),
+        }
+    }
+
+    /// Selects the hunk at the top of the diff pane for export, or deselects it.
+    fn toggle_hunk_selection(&mut self) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(hunk) = hunk_at(file, self.scroll_offset) else {
+            self.flash("No hunk to select".to_string());
+            return;
+        };
+
+        let key = self.hunk_key(self.selected_file, &file.lines[hunk.start]);
+        if !self.selected_hunks.remove(&key) {
+            self.selected_hunks.insert(key);
+        }
+        self.flash(format!("{} hunks selected", self.selected_hunks.len()));
+    }
+
+    /// Returns whether the hunk starting at `header` in the file at `index` is selected.
+    pub fn is_hunk_selected(&self, index: usize, header: &DiffLine) -> bool {
+        // Skips building the key for every header drawn while nothing is selected
+        !self.selected_hunks.is_empty()
+            && self.selected_hunks.contains(&self.hunk_key(index, header))
+    }
+
+    /// Identifies a hunk of the file at `index` across reloads. In status mode the same file
+    /// can be listed in both sections, so the key includes which one it is in.
+    fn hunk_key(&self, index: usize, header: &DiffLine) -> (bool, String, String) {
+        let name = self.files.get(index).map_or("", |file| file.get_name());
+        (
+            self.is_staged(index),
+            name.to_string(),
+            header.content.clone(),
+        )
+    }
+
+    /// Builds one patch from the selected hunks, in file list order, and counts the hunks.
+    fn selected_patch(&self) -> (String, usize) {
+        let mut patch = String::new();
+        let mut count = 0;
+        for (index, file) in self.files.iter().enumerate() {
+            let hunks: Vec<_> = hunk_ranges(file)
+                .into_iter()
+                .filter(|hunk| self.is_hunk_selected(index, &file.lines[hunk.start]))
+                .collect();
+            if !hunks.is_empty() {
+                patch.push_str(&file_patch(file, &hunks));
+                count += hunks.len();
+            }
+        }
+
+        (patch, count)
+    }
+
+    /// Writes the selected hunks to a patch file in the current directory: a plain patch for
+    /// `git apply`, or with a commit `message`, a `git format-patch` style patch for `git am`.
+    fn export_hunks(&mut self, message: Option<&str>) {
+        let (patch, count) = self.selected_patch();
+        if count == 0 {
+            self.flash("No hunks selected; press Space to select the hunk at the top".to_string());
+            return;
+        }
+
+        let result = match message {
+            Some(message) => git::run(&["var", "GIT_AUTHOR_IDENT"]).map(|ident| {
+                // The ident ends with the timestamp and time zone
+                let author = ident.trim().rsplitn(3, ' ').last().unwrap_or_default();
+                let subject = message.trim().lines().next().unwrap_or_default();
+                (
+                    patch_file_name(subject),
+                    format_patch(&patch, author, message),
+                )
+            }),
+            None => Ok((SELECTED_HUNKS_FILE.to_string(), patch)),
+        };
+        let result = result.and_then(|(file_name, text)| {
+            let [file_name] = unused_names(Path::new("."), [file_name.as_str()]);
+            write_new_file(&file_name, &text)?;
+            Ok(file_name)
+        });
+
+        match result {
+            Ok(file_name) => self.flash(format!("Wrote {count} hunks to {file_name}")),
+            Err(err) => self.show_message(format!("Failed to export hunks: {err:#}")),
+        }
+    }
+
+    fn start_comment(&mut self) {
+        if self.current_line().is_some() {
+            self.comment_draft = Some(String::new());
+        } else {
+            self.show_message("No line to comment on".to_string());
+        }
+    }
+
+    fn save_comment(&mut self) {
+        let Some(body) = self.comment_draft.take() else {
+            return;
+        };
+        if body.trim().is_empty() {
+            return;
+        }
+        let Some((file, line)) = self.current_line() else {
+            return;
+        };
+
+        let comment = Comment::new(file.get_name(), line, body);
+        if let Err(err) = self.comments.add(comment) {
+            self.show_message(format!("{err:#}"));
+        }
+    }
+
+    /// Writes the comments as Markdown and as GitHub review JSON to the current directory.
+    fn export_comments(&mut self) {
+        let count = self.comments.comments.len();
+        let [markdown_file, json_file] =
+            unused_names(Path::new("."), [COMMENTS_MARKDOWN_FILE, COMMENTS_JSON_FILE]);
+        let result = self.comments.to_github_json().and_then(|json| {
+            write_new_file(&markdown_file, &self.comments.to_markdown(&self.files))?;
+            write_new_file(&json_file, &json)
+        });
+
+        self.show_message(match result {
+            Ok(()) => format!("Exported {count} comments to {markdown_file} and {json_file}"),
+            Err(err) => format!("Failed to export comments: {err:#}"),
+        });
+    }
+
     fn scroll_down(&mut self, scroll_amount: usize) {
         if let Some(file) = self.files.get(self.selected_file) {
             let new_offset = min(self.scroll_offset + scroll_amount, file.line_count());
@@ -149,6 +808,154 @@ impl App {
         self.horizontal_scroll_offset += amount;
     }
 
+    fn next_commit(&mut self, amount: usize) {
+        self.select_commit(self.selected_commit + amount);
+    }
+
+    fn previous_commit(&mut self, amount: usize) {
+        self.select_commit(self.selected_commit.saturating_sub(amount));
+    }
+
+    /// Moves to the last commit of the range, loading every remaining page.
+    fn last_commit(&mut self) {
+        let Some(log) = self.commit_log.as_mut() else {
+            return;
+        };
+        let mut error = None;
+        while !log.is_exhausted() {
+            if let Err(err) = log.load_more() {
+                error = Some(err);
+                break;
+            }
+        }
+        let last = log.commits.len().saturating_sub(1);
+
+        self.select_commit(last);
+        if let Some(err) = error {
+            self.show_message(format!("Failed to load commits: {err:#}"));
+        }
+    }
+
+    fn select_commit(&mut self, index: usize) {
+        let Some(log) = self.commit_log.as_mut() else {
+            return;
+        };
+
+        // Fetch the next page before the selection reaches the end of what is loaded
+        if index.saturating_add(LOG_PREFETCH_DISTANCE) >= log.commits.len()
+            && !log.is_exhausted()
+            && let Err(err) = log.load_more()
+        {
+            // The log is still borrowed, so this sets the fields `show_message` would
+            self.status_message = Some(format!("Failed to load commits: {err:#}"));
+            self.status_expires_at = None;
+        }
+
+        if !log.commits.is_empty() {
+            self.selected_commit = index.min(log.commits.len() - 1);
+            self.commit_list_state.select(Some(self.selected_commit));
+        }
+    }
+
+    /// Loads the diff of the selected commit into the file list and diff panes.
+    fn open_commit(&mut self) {
+        let Some(commit) = self
+            .commit_log
+            .as_ref()
+            .and_then(|log| log.commits.get(self.selected_commit))
+        else {
+            return;
+        };
+        let hash = commit.hash.clone();
+
+        match self.load_commit(&hash) {
+            Ok(()) => {
+                self.status_message = None;
+                self.view = View::Diff;
+            }
+            Err(err) => self.show_message(format!("{err:#}")),
+        }
+    }
+
+    fn return_to_log(&mut self) {
+        if self.commit_log.is_some() {
+            self.view = View::Log;
+        }
+    }
+
+    fn next_step(&mut self) {
+        if let Some(steps) = &self.commit_steps
+            && steps.position < steps.commits.len()
+        {
+            self.step_to(steps.position + 1);
+        }
+    }
+
+    fn previous_step(&mut self) {
+        if let Some(steps) = &self.commit_steps
+            && steps.position > 0
+        {
+            self.step_to(steps.position - 1);
+        }
+    }
+
+    fn select_conflict_file(&mut self, index: usize) {
+        if !self.conflicts.is_empty() {
+            self.selected_conflict_file = index.min(self.conflicts.len() - 1);
+            self.conflict_file_state
+                .select(Some(self.selected_conflict_file));
+            self.selected_region = 0;
+        }
+    }
+
+    fn select_region(&mut self, index: usize) {
+        if let Some(file) = self.conflicts.get(self.selected_conflict_file) {
+            let count = file.regions().count();
+            self.selected_region = index.min(count.saturating_sub(1));
+        }
+    }
+
+    /// Returns the selected conflict region of the selected file.
+    pub fn current_region(&self) -> Option<&ConflictRegion> {
+        self.conflicts
+            .get(self.selected_conflict_file)?
+            .regions()
+            .nth(self.selected_region)
+    }
+
+    fn resolve_region(&mut self, resolution: Option<Resolution>) {
+        let Some(region) = self
+            .conflicts
+            .get_mut(self.selected_conflict_file)
+            .and_then(|file| file.region_mut(self.selected_region))
+        else {
+            return;
+        };
+        let resolved = resolution.is_some();
+        region.resolution = resolution;
+
+        // Move on to the next conflict once this one is settled
+        if resolved {
+            self.select_region(self.selected_region + 1);
+        }
+    }
+
+    fn write_resolved(&mut self) {
+        let Some(file) = self.conflicts.get(self.selected_conflict_file) else {
+            return;
+        };
+
+        let message = match file.write() {
+            Ok(()) => format!("Wrote and staged {}", file.path),
+            Err(err) => format!("{err:#}"),
+        };
+        self.show_message(message);
+    }
+
+    fn toggle_commit_header(&mut self) {
+        self.expand_commit_header = !self.expand_commit_header;
+    }
+
     fn toggle_view_mode(&mut self, width: u16) {
         self.show_side_by_side = width >= 120 && !self.show_side_by_side;
     }
@@ -202,68 +1009,105 @@ impl App {
     }
 }
 
-fn parse_diff(diff_text: &str) -> Vec<DiffFile> {
-    let mut files = Vec::new();
-    let mut current_file: Option<DiffFile> = None;
-    let mut old_line_num = 0u32;
-    let mut new_line_num = 0u32;
+/// Returns `names`, or when any of those files exist in `dir`, the names with the first
+/// suffix ("-2", "-3", ...) before the extension that makes them all new, so that exports
+/// never overwrite a file.
+fn unused_names<const N: usize>(dir: &Path, names: [&str; N]) -> [String; N] {
+    let with_suffix = |name: &str, n: usize| match (n, name.rsplit_once('.')) {
+        (1, _) => name.to_string(),
+        (_, Some((stem, extension))) => format!("{stem}-{n}.{extension}"),
+        (_, None) => format!("{name}-{n}"),
+    };
 
-    for line in diff_text.lines() {
-        if line.starts_with("diff --git") {
-            if let Some(file) = current_file.take() {
-                files.push(file);
-            }
+    (1..)
+        .map(|n| names.map(|name| with_suffix(name, n)))
+        .find(|candidates| candidates.iter().all(|name| !dir.join(name).exists()))
+        .expect("some suffix is unused")
+}
 
-            // Extract filename from "diff --git a/file b/file"
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                let filename = parts[3].trim_start_matches("b/");
-                current_file = Some(DiffFile::new(filename));
-            }
-        } else if line.starts_with("@@") {
-            // Parse hunk header: @@ -old_start,old_count +new_start,new_count @@
-            if let Some(ref mut file) = current_file {
-                if let Some((old_info, new_info)) = line.split_once(' ').and_then(|(_, rest)| {
-                    rest.split_once(' ').map(|(old, new)| {
-                        (old.trim_start_matches('-'), new.trim_start_matches('+'))
-                    })
-                }) {
-                    old_line_num = old_info
-                        .split(',')
-                        .next()
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(1);
-                    new_line_num = new_info
-                        .split(',')
-                        .next()
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(1);
-                }
+/// Writes a file that must not exist yet.
+fn write_new_file(name: &str, text: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(name)
+        .with_context(|| format!("failed to create {name}"))?;
+    file.write_all(text.as_bytes())
+        .with_context(|| format!("failed to write {name}"))
+}
 
-                file.add_line(DiffLine::new_header(line));
-            }
-        } else if line.starts_with('+') && !line.starts_with("+++") {
-            if let Some(ref mut file) = current_file {
-                file.add_line(DiffLine::new_added(line, new_line_num));
-                new_line_num += 1;
-            }
-        } else if line.starts_with('-') && !line.starts_with("---") {
-            if let Some(ref mut file) = current_file {
-                file.add_line(DiffLine::new_removed(line, old_line_num));
-                old_line_num += 1;
-            }
-        } else if line.starts_with(' ')
-            && let Some(ref mut file) = current_file
-        {
-            file.add_line(DiffLine::new_context(line, old_line_num, new_line_num));
-            old_line_num += 1;
-            new_line_num += 1;
-        }
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::commit_log::Commit;
+
+    fn patch(names: &[&str]) -> String {
+        names
+            .iter()
+            .map(|name| format!("diff --git a/{name} b/{name}\n--- a/{name}\n+++ b/{name}\n@@ -1 +1 @@\n-a\n+b\n"))
+            .collect()
     }
 
-    if let Some(file) = current_file {
-        files.push(file);
+    #[test]
+    fn test_reload_keeps_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("changes.patch");
+        fs::write(&path, patch(&["a.rs", "b.rs", "c.rs"])).unwrap();
+
+        let mut app = App::new(false);
+        app.load_diff(&DiffSource::PatchFile(path.clone())).unwrap();
+        app.select_file(1);
+        app.scroll_offset = 2;
+        let line = app.files[1].lines[1].clone();
+        app.comments
+            .add(Comment::new("b.rs", &line, "Why?".to_string()))
+            .unwrap();
+
+        fs::write(&path, patch(&["b.rs", "c.rs"])).unwrap();
+        app.reload().unwrap();
+
+        assert_eq!(app.files.len(), 2);
+        assert_eq!(app.selected_file, 0);
+        assert_eq!(app.files[app.selected_file].get_name(), "b.rs");
+        assert_eq!(app.scroll_offset, 2);
+        assert_eq!(app.comments.comments.len(), 1);
     }
 
-    files
+    #[test]
+    fn test_last_commit() {
+        let commits = (0..3)
+            .map(|n| Commit {
+                hash: format!("{n:040}"),
+                short_hash: format!("{n:07}"),
+                author: "A U Thor".to_string(),
+                date: "2024-01-01".to_string(),
+                subject: format!("Commit {n}"),
+            })
+            .collect();
+
+        let mut app = App::new(false);
+        app.show_log(CommitLog::from_commits(commits));
+        app.last_commit();
+
+        assert_eq!(app.selected_commit, 2);
+        assert_eq!(app.commit_list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn test_unused_names() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            unused_names(dir.path(), ["a.md", "a.json"]),
+            ["a.md", "a.json"]
+        );
+
+        fs::write(dir.path().join("a.json"), "").unwrap();
+        fs::write(dir.path().join("a-2.md"), "").unwrap();
+        assert_eq!(
+            unused_names(dir.path(), ["a.md", "a.json"]),
+            ["a-3.md", "a-3.json"]
+        );
+    }
 }
@@ -0,0 +1,85 @@
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+
+use crate::temp_file::write_temp_file;
+
+/// Largest text sent through OSC 52; many terminals drop longer sequences.
+const MAX_OSC52_BYTES: usize = 75_000;
+
+/// Where copied text ended up.
+pub enum Copied {
+    Clipboard,
+    File(PathBuf),
+}
+
+/// Copies `text` to the clipboard with an OSC 52 escape sequence, which the terminal handles
+/// itself, so it works over SSH without a clipboard daemon. Text too long for that, or a
+/// terminal that can't take it, gets written to a new file in the temp directory instead.
+pub fn copy(text: &str) -> Result<Copied> {
+    let dumb_terminal = env::var("TERM").map_or(true, |term| term == "dumb");
+    if !dumb_terminal && text.len() <= MAX_OSC52_BYTES {
+        let mut stdout = io::stdout();
+        if stdout
+            .write_all(osc52(text, env::var_os("TMUX").is_some()).as_bytes())
+            .and_then(|()| stdout.flush())
+            .is_ok()
+        {
+            return Ok(Copied::Clipboard);
+        }
+    }
+
+    let (_, path) = write_temp_file("clipboard", text)?
+        .keep()
+        .context("failed to keep the temporary file")?;
+    Ok(Copied::File(path))
+}
+
+/// Builds the escape sequence that sets the clipboard, wrapped for tmux to pass through.
+fn osc52(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | u32::from(byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(base64(b"diff"), "ZGlmZg==");
+        assert_eq!(base64(b"scape"), "c2NhcGU=");
+        assert_eq!(base64(b"abc"), "YWJj");
+        assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(osc52("hi", true), "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\");
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    diff_file::{DiffFile, DiffLine, LineType},
+    diff_source::DiffSource,
+    state::state_file,
+};
+
+/// A review comment anchored to a line of a file's diff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    pub path: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub body: String,
+}
+
+impl Comment {
+    pub fn new(path: &str, line: &DiffLine, body: String) -> Self {
+        Self {
+            path: path.to_string(),
+            old_line: line.old_line_num,
+            new_line: line.new_line_num,
+            body,
+        }
+    }
+
+    pub fn is_on(&self, path: &str, line: &DiffLine) -> bool {
+        self.path == path
+            && self.old_line == line.old_line_num
+            && self.new_line == line.new_line_num
+    }
+
+    /// Describes the anchor as GitHub does: a line number on the new (`RIGHT`) side, or on the
+    /// old (`LEFT`) side for removed lines.
+    fn github_anchor(&self) -> (u32, &'static str) {
+        match (self.new_line, self.old_line) {
+            (Some(line), _) => (line, "RIGHT"),
+            (None, Some(line)) => (line, "LEFT"),
+            (None, None) => (0, "RIGHT"),
+        }
+    }
+}
+
+/// The review comments for a range, persisted under `.git/diffscape/`.
+#[derive(Default)]
+pub struct Comments {
+    /// Where the comments are saved, or `None` when the source is not a repository range.
+    path: Option<PathBuf>,
+    pub comments: Vec<Comment>,
+}
+
+impl Comments {
+    /// Loads the comments saved for the range compared by `source`.
+    pub fn load(source: &DiffSource) -> Result<Self> {
+        let Some(path) = state_file(source, "comments")? else {
+            return Ok(Self::default());
+        };
+
+        let comments = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("failed to parse comments in {}", path.display()))?,
+            Err(_) => Vec::new(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            comments,
+        })
+    }
+
+    /// Adds a comment and saves the comments.
+    pub fn add(&mut self, comment: Comment) -> Result<()> {
+        self.comments.push(comment);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.comments)?)
+            .with_context(|| format!("failed to save comments to {}", path.display()))
+    }
+
+    /// Formats the comments as a Markdown review summary, quoting the commented lines from
+    /// `files`.
+    pub fn to_markdown(&self, files: &[DiffFile]) -> String {
+        let mut markdown = String::from("# Review\n");
+
+        for comment in &self.comments {
+            let (line, _) = comment.github_anchor();
+            markdown.push_str(&format!("\n### `{}` line {line}\n\n", comment.path));
+
+            let quoted = files
+                .iter()
+                .filter(|file| file.get_name() == comment.path)
+                .flat_map(|file| &file.lines)
+                .find(|diff_line| comment.is_on(&comment.path, diff_line));
+            if let Some(diff_line) = quoted {
+                let prefix = match diff_line.line_type {
+                    LineType::Added => '+',
+                    LineType::Removed => '-',
+                    _ => ' ',
+                };
+                markdown.push_str(&format!("```diff\n{prefix}{}\n```\n\n", diff_line.content));
+            }
+
+            markdown.push_str(&comment.body);
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+
+    /// Formats the comments as the body of a GitHub "create a review" request, which leaves
+    /// the review pending so it can be submitted from the pull request.
+    pub fn to_github_json(&self) -> Result<String> {
+        let comments: Vec<_> = self
+            .comments
+            .iter()
+            .map(|comment| {
+                let (line, side) = comment.github_anchor();
+                json!({
+                    "path": comment.path,
+                    "line": line,
+                    "side": side,
+                    "body": comment.body,
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string_pretty(
+            &json!({ "comments": comments }),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let mut file = DiffFile::new("src/lib.rs");
+        file.add_line(DiffLine::new_removed("-old", 4));
+        file.add_line(DiffLine::new_added("+new", 4));
+
+        let mut comments = Comments::default();
+        comments
+            .add(Comment::new(
+                "src/lib.rs",
+                &file.lines[0],
+                "Why?".to_string(),
+            ))
+            .unwrap();
+        comments
+            .add(Comment::new(
+                "src/lib.rs",
+                &file.lines[1],
+                "Nice".to_string(),
+            ))
+            .unwrap();
+
+        assert!(comments.comments[1].is_on("src/lib.rs", &file.lines[1]));
+        assert!(!comments.comments[1].is_on("src/lib.rs", &file.lines[0]));
+        assert!(
+            comments
+                .to_markdown(&[file])
+                .contains("### `src/lib.rs` line 4\n\n```diff\n-old\n```\n\nWhy?\n")
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&comments.to_github_json().unwrap()).unwrap();
+        assert_eq!(json["comments"][0]["side"], "LEFT");
+        assert_eq!(json["comments"][1]["side"], "RIGHT");
+        assert_eq!(json["comments"][1]["line"], 4);
+    }
+}
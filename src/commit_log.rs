@@ -0,0 +1,191 @@
+use anyhow::{Result, anyhow};
+
+use crate::git;
+
+/// Number of commits fetched from git at a time.
+const PAGE_SIZE: usize = 200;
+
+/// Separates the fields of a commit in the `git log` output.
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// The `git log` format matching `parse_commit_line`.
+const COMMIT_FORMAT: &str = "--format=%H%x1f%h%x1f%an%x1f%ad%x1f%s";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+}
+
+impl Commit {
+    /// Loads the summary of the commit named by `rev`.
+    pub fn load(rev: &str) -> Result<Self> {
+        let output = git::run(&["log", "-1", COMMIT_FORMAT, "--date=short", rev, "--"])?;
+
+        parse_commit_line(output.trim_end())
+            .ok_or_else(|| anyhow!("unexpected git log output for {rev}"))
+    }
+}
+
+/// The full metadata of a single commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitDetails {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub author_date: String,
+    pub committer: String,
+    pub committer_date: String,
+    pub message: String,
+}
+
+impl CommitDetails {
+    /// Loads the metadata of the commit named by `rev`.
+    pub fn load(rev: &str) -> Result<Self> {
+        let output = git::run(&[
+            "show",
+            "--no-patch",
+            "--date=iso",
+            "--format=%H%x1f%P%x1f%an <%ae>%x1f%ad%x1f%cn <%ce>%x1f%cd%x1f%B",
+            rev,
+            "--",
+        ])?;
+
+        parse_commit_details(&output).ok_or_else(|| anyhow!("unexpected git show output for {rev}"))
+    }
+
+    /// Returns the first line of the commit message.
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// The history of a branch or range, loaded lazily one page at a time.
+pub struct CommitLog {
+    range: Option<String>,
+    paths: Vec<String>,
+    pub commits: Vec<Commit>,
+    exhausted: bool,
+}
+
+impl CommitLog {
+    /// Creates a log for `range` (or the current branch), limited to commits touching `paths`,
+    /// and loads the first page.
+    pub fn new(range: Option<String>, paths: Vec<String>) -> Result<Self> {
+        git::ensure_repository()?;
+
+        let mut log = Self {
+            range,
+            paths,
+            commits: Vec::new(),
+            exhausted: false,
+        };
+        log.load_more()?;
+
+        Ok(log)
+    }
+
+    /// Returns true once every commit in the range has been loaded.
+    /// A log holding exactly `commits`, with no more pages to load.
+    #[cfg(test)]
+    pub fn from_commits(commits: Vec<Commit>) -> Self {
+        Self {
+            range: None,
+            paths: Vec::new(),
+            commits,
+            exhausted: true,
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Fetches the next page of commits, if there is one.
+    pub fn load_more(&mut self) -> Result<()> {
+        if self.exhausted {
+            return Ok(());
+        }
+
+        let mut args = vec![
+            "log".to_string(),
+            COMMIT_FORMAT.to_string(),
+            "--date=short".to_string(),
+            format!("--skip={}", self.commits.len()),
+            format!("--max-count={PAGE_SIZE}"),
+        ];
+        args.extend(self.range.iter().cloned());
+        args.push("--".to_string());
+        args.extend(self.paths.iter().cloned());
+
+        let output = git::run(&args)?;
+        let page: Vec<Commit> = output.lines().filter_map(parse_commit_line).collect();
+
+        self.exhausted = page.len() < PAGE_SIZE;
+        self.commits.extend(page);
+
+        Ok(())
+    }
+}
+
+fn parse_commit_line(line: &str) -> Option<Commit> {
+    let mut fields = line.splitn(5, FIELD_SEPARATOR);
+
+    Some(Commit {
+        hash: fields.next()?.to_string(),
+        short_hash: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        date: fields.next()?.to_string(),
+        subject: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+fn parse_commit_details(output: &str) -> Option<CommitDetails> {
+    let mut fields = output.splitn(7, FIELD_SEPARATOR);
+
+    Some(CommitDetails {
+        hash: fields.next()?.to_string(),
+        parents: fields
+            .next()?
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        author: fields.next()?.to_string(),
+        author_date: fields.next()?.to_string(),
+        committer: fields.next()?.to_string(),
+        committer_date: fields.next()?.to_string(),
+        message: fields.next()?.trim_end().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_line() {
+        let line = "0123456789abcdef\x1f0123456\x1fJane Doe\x1f2024-05-01\x1fFix: a\x1fb";
+        let commit = parse_commit_line(line).unwrap();
+
+        assert_eq!(commit.short_hash, "0123456");
+        assert_eq!(commit.author, "Jane Doe");
+        assert_eq!(commit.date, "2024-05-01");
+        assert_eq!(commit.subject, "Fix: a\x1fb");
+        assert!(parse_commit_line("").is_none());
+    }
+
+    #[test]
+    fn test_parse_commit_details() {
+        let output =
+            "abc\x1fp1 p2\x1fA <a@x>\x1f2024-01-01\x1fC <c@x>\x1f2024-01-02\x1fSubject\n\nBody\n\n";
+        let details = parse_commit_details(output).unwrap();
+
+        assert_eq!(details.parents, vec!["p1", "p2"]);
+        assert_eq!(details.committer, "C <c@x>");
+        assert_eq!(details.message, "Subject\n\nBody");
+        assert_eq!(details.subject(), "Subject");
+    }
+}
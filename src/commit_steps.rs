@@ -0,0 +1,63 @@
+use anyhow::Result;
+
+use crate::{diff_source::DiffSource, git};
+
+/// Steps through the commits of a range one at a time, with the squashed range diff as an
+/// extra position before the first commit.
+pub struct CommitSteps {
+    pub range: String,
+    pub commits: Vec<String>,
+    /// 0 is the squashed range diff, `n` is the n-th commit (oldest first).
+    pub position: usize,
+    /// Options such as `-w` or `-U10`, passed to the diff of every position.
+    options: Vec<String>,
+    paths: Vec<String>,
+}
+
+impl CommitSteps {
+    /// Enumerates the commits of `range` that touch `paths`, oldest first.
+    ///
+    /// A symmetric range `A...B` steps through the commits of `A..B`, while the whole-range
+    /// diff is taken from their merge base.
+    pub fn new(range: &str, options: Vec<String>, paths: Vec<String>) -> Result<Self> {
+        let mut args = vec![
+            "rev-list".to_string(),
+            "--reverse".to_string(),
+            range.replacen("...", "..", 1),
+            "--".to_string(),
+        ];
+        args.extend(paths.iter().cloned());
+        let commits = git::run(&args)?.lines().map(str::to_string).collect();
+
+        Ok(Self {
+            range: range.to_string(),
+            commits,
+            position: 0,
+            options,
+            paths,
+        })
+    }
+
+    /// Returns the hash of the current commit, or `None` for the squashed range diff.
+    pub fn current_commit(&self) -> Option<&str> {
+        self.position
+            .checked_sub(1)
+            .and_then(|index| self.commits.get(index))
+            .map(String::as_str)
+    }
+
+    /// Returns the source for the diff at the current position.
+    pub fn current_source(&self) -> Result<DiffSource> {
+        let mut args = self.options.clone();
+        match self.current_commit() {
+            Some(commit) if self.options.is_empty() => {
+                return DiffSource::for_commit(commit, &self.paths);
+            }
+            Some(commit) => args.extend(git::commit_range(commit)?),
+            None => args.push(self.range.clone()),
+        }
+        args.push("--".to_string());
+        args.extend(self.paths.iter().cloned());
+        Ok(DiffSource::Git(args))
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use similar::{DiffOp, DiffTag, TextDiff};
+
+use crate::git;
+
+/// How a conflict region has been resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// Our lines followed by theirs.
+    Both,
+    Edited(Vec<String>),
+}
+
+/// One conflict between `<<<<<<<` and `>>>>>>>` markers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictRegion {
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    /// The common ancestor's lines, when known.
+    pub base: Option<Vec<String>>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+    pub resolution: Option<Resolution>,
+}
+
+impl ConflictRegion {
+    /// Returns the lines that replace the region: the chosen resolution, or the original
+    /// conflict markers while it is unresolved.
+    pub fn result_lines(&self) -> Vec<String> {
+        match &self.resolution {
+            Some(Resolution::Ours) => self.ours.clone(),
+            Some(Resolution::Theirs) => self.theirs.clone(),
+            Some(Resolution::Both) => [self.ours.clone(), self.theirs.clone()].concat(),
+            Some(Resolution::Edited(lines)) => lines.clone(),
+            None => {
+                let mut lines = vec![format!("<<<<<<< {}", self.ours_label)];
+                lines.extend(self.ours.iter().cloned());
+                lines.push("=======".to_string());
+                lines.extend(self.theirs.iter().cloned());
+                lines.push(format!(">>>>>>> {}", self.theirs_label));
+                lines
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Clean(Vec<String>),
+    Conflict(ConflictRegion),
+}
+
+/// A file with merge conflicts, split into clean stretches and conflict regions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictFile {
+    /// The path relative to the repository root.
+    pub path: String,
+    pub segments: Vec<Segment>,
+    trailing_newline: bool,
+    /// The file's line ending, kept when writing it back.
+    line_ending: &'static str,
+}
+
+impl ConflictFile {
+    /// Lists the files with unresolved conflicts in the current repository, along with the
+    /// conflicted paths that can't be resolved here, such as binary files or files deleted on
+    /// one side.
+    pub fn load_all() -> Result<(Vec<Self>, Vec<String>)> {
+        git::ensure_repository()?;
+
+        let root = PathBuf::from(git::run(&["rev-parse", "--show-toplevel"])?.trim());
+        let output = git::run(&["diff", "--name-only", "--diff-filter=U"])?;
+
+        // The name list repeats a path for each unmerged stage in some git versions
+        let mut paths: Vec<&str> = output.lines().collect();
+        paths.dedup();
+
+        let mut files = Vec::new();
+        let mut unsupported = Vec::new();
+        for path in paths {
+            let full_path = root.join(path);
+            let reason = if let Some(side) = one_sided(&root, path) {
+                side
+            } else if full_path.is_dir() {
+                "submodule"
+            } else {
+                let bytes =
+                    fs::read(&full_path).with_context(|| format!("failed to read {path}"))?;
+                match String::from_utf8(bytes) {
+                    Ok(text) if !text.contains('\0') => {
+                        files.push(Self::load(path, &text));
+                        continue;
+                    }
+                    _ => "binary",
+                }
+            };
+            unsupported.push(format!("{path} ({reason})"));
+        }
+
+        Ok((files, unsupported))
+    }
+
+    fn load(path: &str, text: &str) -> Self {
+        let mut file = Self::parse(path, text);
+
+        // Without diff3-style markers, find each region's base through the index stages
+        if file.regions().any(|region| region.base.is_none())
+            && let Ok(ours) = git::run(&["show", &format!(":2:{path}")])
+            && let Ok(base) = git::run(&["show", &format!(":1:{path}")])
+        {
+            file.fill_bases(&ours, &base);
+        }
+
+        file
+    }
+
+    /// Splits `text` on its conflict markers.
+    pub fn parse(path: &str, text: &str) -> Self {
+        enum Section {
+            Clean,
+            Ours,
+            Base,
+            Theirs,
+        }
+
+        let mut segments = Vec::new();
+        let mut clean = Vec::new();
+        let mut region: Option<ConflictRegion> = None;
+        let mut section = Section::Clean;
+        // The original text of the open region, kept in case it is never closed
+        let mut region_text = Vec::new();
+
+        for line in text.lines() {
+            if region.is_some() {
+                region_text.push(line.to_string());
+            }
+            match (&section, marker(line)) {
+                (Section::Clean, Some(('<', label))) => {
+                    if !clean.is_empty() {
+                        segments.push(Segment::Clean(std::mem::take(&mut clean)));
+                    }
+                    region_text = vec![line.to_string()];
+                    region = Some(ConflictRegion {
+                        ours_label: label.to_string(),
+                        ours: Vec::new(),
+                        base: None,
+                        theirs_label: String::new(),
+                        theirs: Vec::new(),
+                        resolution: None,
+                    });
+                    section = Section::Ours;
+                }
+                (Section::Ours, Some(('|', _))) => {
+                    if let Some(region) = region.as_mut() {
+                        region.base = Some(Vec::new());
+                    }
+                    section = Section::Base;
+                }
+                (Section::Ours | Section::Base, Some(('=', _))) => section = Section::Theirs,
+                (Section::Theirs, Some(('>', label))) => {
+                    if let Some(mut region) = region.take() {
+                        region.theirs_label = label.to_string();
+                        segments.push(Segment::Conflict(region));
+                    }
+                    section = Section::Clean;
+                }
+                _ => {
+                    let line = line.to_string();
+                    match (&section, region.as_mut()) {
+                        (Section::Ours, Some(region)) => region.ours.push(line),
+                        (Section::Base, Some(region)) => {
+                            region.base.get_or_insert_with(Vec::new).push(line)
+                        }
+                        (Section::Theirs, Some(region)) => region.theirs.push(line),
+                        _ => clean.push(line),
+                    }
+                }
+            }
+        }
+
+        // An unterminated region is kept as plain text
+        if region.is_some() {
+            clean.extend(region_text);
+        }
+        if !clean.is_empty() {
+            segments.push(Segment::Clean(clean));
+        }
+
+        Self {
+            path: path.to_string(),
+            segments,
+            trailing_newline: text.ends_with('\n'),
+            line_ending: if text.contains("\r\n") { "\r\n" } else { "\n" },
+        }
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &ConflictRegion> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Conflict(region) => Some(region),
+            Segment::Clean(_) => None,
+        })
+    }
+
+    fn regions_mut(&mut self) -> impl Iterator<Item = &mut ConflictRegion> {
+        self.segments
+            .iter_mut()
+            .filter_map(|segment| match segment {
+                Segment::Conflict(region) => Some(region),
+                Segment::Clean(_) => None,
+            })
+    }
+
+    /// Fills in the base of each region from the `ours` and `base` versions of the file, as
+    /// long as the clean lines and our side of each region still match `ours`.
+    fn fill_bases(&mut self, ours: &str, base: &str) {
+        let ours: Vec<&str> = ours.lines().collect();
+        let base: Vec<&str> = base.lines().collect();
+
+        let mut position = 0;
+        let mut ranges = Vec::new();
+        for segment in &self.segments {
+            let lines = match segment {
+                Segment::Clean(lines) => lines,
+                Segment::Conflict(region) => {
+                    ranges.push(position..position + region.ours.len());
+                    &region.ours
+                }
+            };
+            let matches = ours
+                .get(position..position + lines.len())
+                .is_some_and(|expected| expected.iter().eq(lines));
+            if !matches {
+                return;
+            }
+            position += lines.len();
+        }
+        if position != ours.len() {
+            return;
+        }
+
+        let diff = TextDiff::from_slices(&base, &ours);
+        for (region, range) in self.regions_mut().zip(ranges) {
+            if region.base.is_none() {
+                let base_lines = &base[base_range(diff.ops(), base.len(), range)];
+                region.base = Some(base_lines.iter().map(|line| line.to_string()).collect());
+            }
+        }
+    }
+
+    /// Returns the `index`-th conflict region.
+    pub fn region_mut(&mut self, index: usize) -> Option<&mut ConflictRegion> {
+        self.regions_mut().nth(index)
+    }
+
+    pub fn unresolved_count(&self) -> usize {
+        self.regions()
+            .filter(|region| region.resolution.is_none())
+            .count()
+    }
+
+    /// Returns the resolved file's lines, and for each conflict region the range of result
+    /// lines it occupies.
+    pub fn result(&self) -> (Vec<String>, Vec<Range<usize>>) {
+        let mut lines = Vec::new();
+        let mut ranges = Vec::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Clean(clean) => lines.extend(clean.iter().cloned()),
+                Segment::Conflict(region) => {
+                    let start = lines.len();
+                    lines.extend(region.result_lines());
+                    ranges.push(start..lines.len());
+                }
+            }
+        }
+
+        (lines, ranges)
+    }
+
+    /// Returns the text of the resolved file, with its original line endings.
+    fn resolved_text(&self) -> String {
+        let (lines, _) = self.result();
+        let mut text = lines.join(self.line_ending);
+        if self.trailing_newline && !lines.is_empty() {
+            text.push_str(self.line_ending);
+        }
+        text
+    }
+
+    /// Writes the resolved file and stages it, failing while any region is unresolved.
+    pub fn write(&self) -> Result<()> {
+        let unresolved = self.unresolved_count();
+        if unresolved > 0 {
+            bail!(
+                "{unresolved} conflicts in {} are still unresolved",
+                self.path
+            );
+        }
+
+        let root = PathBuf::from(git::run(&["rev-parse", "--show-toplevel"])?.trim());
+        fs::write(root.join(&self.path), self.resolved_text())
+            .with_context(|| format!("failed to write {}", self.path))?;
+        git::run(&["-C", &root.to_string_lossy(), "add", "--", &self.path])?;
+
+        Ok(())
+    }
+}
+
+/// Describes a conflict where only one side of the merge has the file at `path`, from the
+/// index stages left for it: stage 1 holds the common ancestor, 2 ours and 3 theirs.
+fn one_sided(root: &Path, path: &str) -> Option<&'static str> {
+    let root = root.to_string_lossy();
+    let stages = git::run(&["-C", &root, "ls-files", "--unmerged", "--", path]).unwrap_or_default();
+    let has_stage = |stage: &str| {
+        stages
+            .lines()
+            .any(|line| line.split_whitespace().nth(2) == Some(stage))
+    };
+
+    match (has_stage("1"), has_stage("2"), has_stage("3")) {
+        (_, true, true) => None,
+        (true, true, false) => Some("deleted by them"),
+        (true, false, true) => Some("deleted by us"),
+        (false, true, false) => Some("added by us"),
+        (false, false, true) => Some("added by them"),
+        _ => Some("deleted by both"),
+    }
+}
+
+/// Recognises a conflict marker line, returning its kind and label.
+fn marker(line: &str) -> Option<(char, &str)> {
+    let kind = line.chars().next()?;
+    if !matches!(kind, '<' | '|' | '=' | '>') {
+        return None;
+    }
+
+    let rest = line.get(7..)?;
+    if !line[..7].chars().all(|c| c == kind) {
+        return None;
+    }
+    match (kind, rest) {
+        ('=', "") => Some((kind, "")),
+        ('=', _) => None,
+        (_, "") => Some((kind, "")),
+        (_, rest) => rest.strip_prefix(' ').map(|label| (kind, label)),
+    }
+}
+
+/// Maps a range of lines in our version to the lines of the base it replaces, using a diff of
+/// the base against ours.
+fn base_range(ops: &[DiffOp], base_len: usize, ours: Range<usize>) -> Range<usize> {
+    let touches = |op: &DiffOp, index: usize| {
+        let new = op.new_range();
+        new.contains(&index) || (new.is_empty() && new.start == index)
+    };
+
+    let start = ops
+        .iter()
+        .find(|op| touches(op, ours.start))
+        .map_or(base_len, |op| match op.tag() {
+            DiffTag::Equal => op.old_range().start + ours.start - op.new_range().start,
+            _ => op.old_range().start,
+        });
+    let end = ops
+        .iter()
+        .rev()
+        .find(|op| {
+            (!ours.is_empty() && op.new_range().contains(&(ours.end - 1)))
+                || (op.new_range().is_empty() && op.new_range().start == ours.end)
+        })
+        .map_or(start, |op| match op.tag() {
+            DiffTag::Equal => op.old_range().start + ours.end - op.new_range().start,
+            _ => op.old_range().end,
+        });
+
+    start..end.max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let text = "\
+fn main() {
+<<<<<<< HEAD
+    one();
+||||||| base
+    zero();
+=======
+    two();
+>>>>>>> feature
+}
+";
+        let mut file = ConflictFile::parse("src/main.rs", text);
+
+        assert_eq!(file.segments.len(), 3);
+        let region = file.regions().next().unwrap();
+        assert_eq!(region.ours_label, "HEAD");
+        assert_eq!(region.base, Some(vec!["    zero();".to_string()]));
+        assert_eq!(region.theirs, vec!["    two();"]);
+        assert_eq!(file.unresolved_count(), 1);
+
+        let (lines, ranges) = file.result();
+        assert_eq!(lines[1], "<<<<<<< HEAD");
+        assert_eq!(ranges, vec![1..6]);
+
+        file.region_mut(0).unwrap().resolution = Some(Resolution::Both);
+        let (lines, ranges) = file.result();
+        assert_eq!(lines, ["fn main() {", "    one();", "    two();", "}"]);
+        assert_eq!(ranges, vec![1..3]);
+        assert_eq!(file.unresolved_count(), 0);
+
+        let mut file = ConflictFile::parse("crlf.txt", &text.replace('\n', "\r\n"));
+        file.region_mut(0).unwrap().resolution = Some(Resolution::Theirs);
+        assert_eq!(file.resolved_text(), "fn main() {\r\n    two();\r\n}\r\n");
+    }
+
+    #[test]
+    fn test_fill_bases() {
+        let text = "a\n<<<<<<< HEAD\nours1\nm\nours2\n=======\ntheirs\n>>>>>>> other\nz\n";
+        let mut file = ConflictFile::parse("f", text);
+        file.fill_bases("a\nours1\nm\nours2\nz\n", "a\nbase1\nm\nbase2\nz\n");
+
+        let region = file.regions().next().unwrap();
+        assert_eq!(
+            region.base,
+            Some(vec![
+                "base1".to_string(),
+                "m".to_string(),
+                "base2".to_string()
+            ])
+        );
+    }
+}
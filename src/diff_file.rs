@@ -1,7 +1,13 @@
+use serde::Serialize;
+
 #[derive(Debug, Clone)]
 pub struct DiffFile {
     name: String,
+    /// The path before a rename or copy.
+    old_name: Option<String>,
     status: char, // M, A, D, etc.
+    /// The mode of an added or deleted file, such as "100755".
+    mode: Option<String>,
     pub lines: Vec<DiffLine>,
 }
 
@@ -11,9 +17,16 @@ pub struct DiffLine {
     pub old_line_num: Option<u32>,
     pub new_line_num: Option<u32>,
     pub content: String,
+    /// The marker column for each parent of a combined (merge) diff, such as `"+ "`. Empty
+    /// for ordinary diffs.
+    pub parent_markers: String,
+    /// Whether the line is the last of its file and has no newline, as noted by a
+    /// "\ No newline at end of file" line in the diff.
+    pub no_newline: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LineType {
     Context,
     Added,
@@ -25,7 +38,9 @@ impl DiffFile {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
+            old_name: None,
             status: 'M', // Default to modified
+            mode: None,
             lines: Vec::new(),
         }
     }
@@ -38,6 +53,10 @@ impl DiffFile {
         self.lines.push(line);
     }
 
+    pub fn set_status(&mut self, status: char) {
+        self.status = status;
+    }
+
     pub fn get_status(&self) -> char {
         self.status
     }
@@ -45,6 +64,22 @@ impl DiffFile {
     pub fn get_name(&self) -> &str {
         &self.name
     }
+
+    pub fn set_old_name(&mut self, name: &str) {
+        self.old_name = Some(name.to_string());
+    }
+
+    pub fn get_old_name(&self) -> Option<&str> {
+        self.old_name.as_deref()
+    }
+
+    pub fn set_mode(&mut self, mode: &str) {
+        self.mode = Some(mode.to_string());
+    }
+
+    pub fn get_mode(&self) -> Option<&str> {
+        self.mode.as_deref()
+    }
 }
 
 impl DiffLine {
@@ -54,6 +89,8 @@ impl DiffLine {
             old_line_num: None,
             new_line_num: None,
             content: line.to_string(),
+            parent_markers: String::new(),
+            no_newline: false,
         }
     }
 
@@ -63,6 +100,8 @@ impl DiffLine {
             old_line_num: None,
             new_line_num: Some(line_number),
             content: line[1..].to_string(),
+            parent_markers: String::new(),
+            no_newline: false,
         }
     }
 
@@ -72,6 +111,8 @@ impl DiffLine {
             old_line_num: Some(line_number),
             new_line_num: None,
             content: line[1..].to_string(),
+            parent_markers: String::new(),
+            no_newline: false,
         }
     }
 
@@ -81,6 +122,8 @@ impl DiffLine {
             old_line_num: Some(old_line_num),
             new_line_num: Some(new_line_num),
             content: line[1..].to_string(),
+            parent_markers: String::new(),
+            no_newline: false,
         }
     }
 }
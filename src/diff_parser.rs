@@ -0,0 +1,433 @@
+use crate::diff_file::{DiffFile, DiffLine, LineType};
+
+/// Parses unified diff text into a list of files.
+///
+/// Accepts `git diff` output (with or without prefixes), `git log -p`/`git show` output
+/// where commit headers are interleaved with file diffs, plain `diff -u` patches that
+/// have no `diff --git` lines at all, and combined diffs of merges (`diff --cc`).
+pub fn parse_diff(diff_text: &str) -> Vec<DiffFile> {
+    let mut files = Vec::new();
+    let mut current_file: Option<DiffFile> = None;
+    let mut old_path: Option<&str> = None;
+    let mut old_line_num = 0u32;
+    let mut new_line_num = 0u32;
+    let mut old_remaining = 0u32;
+    let mut new_remaining = 0u32;
+    let mut combined: Option<CombinedHunk> = None;
+
+    for line in diff_text.lines() {
+        // "\ No newline at end of file" belongs to the content line before it
+        if line.starts_with('\\')
+            && let Some(ref mut file) = current_file
+            && let Some(last) = file.lines.last_mut()
+            && last.line_type != LineType::Header
+        {
+            last.no_newline = true;
+            continue;
+        }
+
+        if let Some(ref mut file) = current_file
+            && let Some(hunk) = combined.as_mut()
+            && hunk.is_open()
+        {
+            if let Some(diff_line) = hunk.parse_line(line) {
+                file.add_line(diff_line);
+                continue;
+            }
+            // The hunk was shorter than its header claimed; treat this line as a header
+            combined = None;
+        }
+
+        // Inside a hunk the line counts from the header decide what is content, so that
+        // lines such as "--- foo" or "diff --git" inside a patch are not mistaken for headers.
+        if let Some(ref mut file) = current_file
+            && (old_remaining > 0 || new_remaining > 0)
+        {
+            match line.chars().next() {
+                Some('+') => {
+                    file.add_line(DiffLine::new_added(line, new_line_num));
+                    new_line_num += 1;
+                    new_remaining = new_remaining.saturating_sub(1);
+                    continue;
+                }
+                Some('-') => {
+                    file.add_line(DiffLine::new_removed(line, old_line_num));
+                    old_line_num += 1;
+                    old_remaining = old_remaining.saturating_sub(1);
+                    continue;
+                }
+                // Some tools strip the trailing space from empty context lines
+                Some(' ') | None => {
+                    let line = if line.is_empty() { " " } else { line };
+                    file.add_line(DiffLine::new_context(line, old_line_num, new_line_num));
+                    old_line_num += 1;
+                    new_line_num += 1;
+                    old_remaining = old_remaining.saturating_sub(1);
+                    new_remaining = new_remaining.saturating_sub(1);
+                    continue;
+                }
+                // The hunk was shorter than its header claimed; treat this line as a header
+                _ => {
+                    old_remaining = 0;
+                    new_remaining = 0;
+                }
+            }
+        }
+
+        if line.starts_with("diff --git") {
+            if let Some(file) = current_file.take() {
+                files.push(file);
+            }
+            old_path = None;
+
+            if let Some(filename) = parse_diff_git_filename(line) {
+                current_file = Some(DiffFile::new(filename));
+            }
+        } else if let Some(filename) = line
+            .strip_prefix("diff --cc ")
+            .or_else(|| line.strip_prefix("diff --combined "))
+        {
+            if let Some(file) = current_file.take() {
+                files.push(file);
+            }
+            old_path = None;
+            current_file = Some(DiffFile::new(filename));
+        } else if line.starts_with("@@@")
+            && let Some(ref mut file) = current_file
+        {
+            combined = CombinedHunk::parse_header(line);
+            file.add_line(DiffLine::new_header(line));
+        } else if let Some(mode) = line.strip_prefix("new file mode ") {
+            if let Some(ref mut file) = current_file {
+                file.set_status('A');
+                file.set_mode(mode);
+            }
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            if let Some(ref mut file) = current_file {
+                file.set_status('D');
+                file.set_mode(mode);
+            }
+        } else if let Some(old_name) = line
+            .strip_prefix("rename from ")
+            .or_else(|| line.strip_prefix("copy from "))
+        {
+            if let Some(ref mut file) = current_file {
+                file.set_status(if line.starts_with("rename") { 'R' } else { 'C' });
+                file.set_old_name(old_name);
+            }
+        } else if line.starts_with("Binary files") {
+            if let Some(ref mut file) = current_file {
+                file.add_line(DiffLine::new_header(line));
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = Some(parse_patch_path(path, "a/"));
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let new_path = parse_patch_path(path, "b/");
+
+            // Plain unified diffs have no "diff --git" line, so the file starts here
+            let starts_new_file = current_file
+                .as_ref()
+                .is_none_or(|file| file.line_count() > 0);
+            if starts_new_file {
+                if let Some(file) = current_file.take() {
+                    files.push(file);
+                }
+                let name = match (old_path, new_path) {
+                    (Some(old), "/dev/null") => old,
+                    _ => new_path,
+                };
+                current_file = Some(DiffFile::new(name));
+            }
+
+            if let Some(ref mut file) = current_file {
+                if old_path == Some("/dev/null") {
+                    file.set_status('A');
+                } else if new_path == "/dev/null" {
+                    file.set_status('D');
+                }
+            }
+        } else if line.starts_with("@@")
+            && let Some(ref mut file) = current_file
+        {
+            if let Some((old, new)) = parse_hunk_header(line) {
+                (old_line_num, old_remaining) = old;
+                (new_line_num, new_remaining) = new;
+            }
+
+            file.add_line(DiffLine::new_header(line));
+        }
+    }
+
+    if let Some(file) = current_file {
+        files.push(file);
+    }
+
+    files
+}
+
+/// Tracks the position within a combined diff hunk, which has a marker column and a line range
+/// for each parent.
+struct CombinedHunk {
+    /// The next line number and remaining line count in each parent.
+    parents: Vec<(u32, u32)>,
+    new_line_num: u32,
+    new_remaining: u32,
+}
+
+impl CombinedHunk {
+    /// Parses a header such as "@@@ -1,5 -1,4 +1,7 @@@", with one range per parent.
+    fn parse_header(line: &str) -> Option<Self> {
+        let at_count = line.chars().take_while(|&c| c == '@').count();
+        let mut ranges = line[at_count..].split_whitespace();
+
+        let parents = (1..at_count)
+            .map(|_| Some(parse_hunk_range(ranges.next()?.strip_prefix('-')?)))
+            .collect::<Option<Vec<_>>>()?;
+        let (new_line_num, new_remaining) = parse_hunk_range(ranges.next()?.strip_prefix('+')?);
+
+        Some(Self {
+            parents,
+            new_line_num,
+            new_remaining,
+        })
+    }
+
+    fn is_open(&self) -> bool {
+        self.new_remaining > 0 || self.parents.iter().any(|&(_, remaining)| remaining > 0)
+    }
+
+    /// Parses a content line of the hunk, or returns `None` if it is not one.
+    fn parse_line(&mut self, line: &str) -> Option<DiffLine> {
+        let columns = self.parents.len();
+        // Some tools strip the trailing spaces from empty context lines
+        let (markers, content) = if line.is_empty() {
+            (" ".repeat(columns), "")
+        } else {
+            let markers = line.get(..columns)?;
+            (markers.to_string(), &line[columns..])
+        };
+        if !markers.chars().all(|c| matches!(c, ' ' | '+' | '-')) {
+            return None;
+        }
+
+        // A removed line is only in the parents marked '-'; any other line is in every parent
+        // not marked '+'
+        let removed = markers.contains('-');
+        let in_parent = |marker: char| {
+            if removed {
+                marker == '-'
+            } else {
+                marker != '+'
+            }
+        };
+
+        let mut old_line_num = None;
+        for (index, (marker, (line_num, remaining))) in
+            markers.chars().zip(self.parents.iter_mut()).enumerate()
+        {
+            if in_parent(marker) {
+                if index == 0 {
+                    old_line_num = Some(*line_num);
+                }
+                *line_num += 1;
+                *remaining = remaining.saturating_sub(1);
+            }
+        }
+
+        let new_line_num = (!removed).then_some(self.new_line_num);
+        if !removed {
+            self.new_line_num += 1;
+            self.new_remaining = self.new_remaining.saturating_sub(1);
+        }
+
+        let line_type = if removed {
+            LineType::Removed
+        } else if markers.contains('+') {
+            LineType::Added
+        } else {
+            LineType::Context
+        };
+
+        Some(DiffLine {
+            line_type,
+            old_line_num,
+            new_line_num,
+            content: content.to_string(),
+            parent_markers: markers,
+            no_newline: false,
+        })
+    }
+}
+
+/// Parses the "(start, count)" ranges of the old and new file from a hunk header such as
+/// "@@ -old_start,old_count +new_start,new_count @@".
+pub fn parse_hunk_header(line: &str) -> Option<((u32, u32), (u32, u32))> {
+    let (_, ranges) = line.split_once(' ')?;
+    let (old, new) = ranges.split_once(' ')?;
+
+    Some((
+        parse_hunk_range(old.strip_prefix('-')?),
+        parse_hunk_range(new.strip_prefix('+')?),
+    ))
+}
+
+/// Parses a "start,count" hunk range, where the count defaults to 1 when omitted.
+fn parse_hunk_range(range: &str) -> (u32, u32) {
+    let range = range.split_whitespace().next().unwrap_or(range);
+    let mut parts = range.split(',');
+    let start = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+    let count = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+    (start, count)
+}
+
+/// Extracts the path from a "--- a/file" or "+++ b/file" line, dropping the git prefix and
+/// any tab-separated timestamp added by `diff -u`.
+fn parse_patch_path<'a>(path: &'a str, prefix: &str) -> &'a str {
+    let path = path.split('\t').next().unwrap_or(path).trim_end();
+    path.strip_prefix(prefix).unwrap_or(path)
+}
+
+/// Extracts the new filename from a "diff --git a/file b/file" line.
+///
+/// When both sides name the same path, the line is split in the middle so that
+/// filenames containing spaces are preserved.
+fn parse_diff_git_filename(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("diff --git ")?;
+
+    let half = rest.len() / 2;
+    if rest.len() % 2 == 1 && rest.is_char_boundary(half) && rest[half..].starts_with(' ') {
+        let old = &rest[..half];
+        let new = &rest[half + 1..];
+        if old.trim_start_matches("a/") == new.trim_start_matches("b/") {
+            return Some(new.trim_start_matches("b/"));
+        }
+    }
+
+    rest.split_whitespace()
+        .nth(1)
+        .map(|name| name.trim_start_matches("b/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_file::LineType;
+
+    #[test]
+    fn test_parse_diff_git_filename() {
+        assert_eq!(
+            parse_diff_git_filename("diff --git src/app.rs src/app.rs"),
+            Some("src/app.rs")
+        );
+        assert_eq!(
+            parse_diff_git_filename("diff --git a/src/app.rs b/src/app.rs"),
+            Some("src/app.rs")
+        );
+        assert_eq!(
+            parse_diff_git_filename("diff --git my file.txt my file.txt"),
+            Some("my file.txt")
+        );
+        assert_eq!(
+            parse_diff_git_filename("diff --git old.rs new.rs"),
+            Some("new.rs")
+        );
+    }
+
+    #[test]
+    fn test_parse_diff_git_log_output() {
+        let text = "\
+commit 0123456789abcdef
+Author: Someone <someone@example.com>
+
+    Add a file
+
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,2 @@
++-- not a header
++hello
+
+commit fedcba9876543210
+Author: Someone <someone@example.com>
+
+    Remove a line
+
+diff --git a/new.txt b/new.txt
+index e69de29..0000000 100644
+--- a/new.txt
++++ b/new.txt
+@@ -1,2 +1,1 @@
+--- not a header
+ hello
+";
+        let files = parse_diff(text);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].get_name(), "new.txt");
+        assert_eq!(files[0].get_status(), 'A');
+        assert_eq!(files[0].line_count(), 3);
+        assert_eq!(files[0].lines[1].content, "-- not a header");
+        assert_eq!(files[1].get_status(), 'M');
+        assert_eq!(files[1].line_count(), 3);
+        assert_eq!(files[1].lines[1].line_type, LineType::Removed);
+        assert_eq!(files[1].lines[2].new_line_num, Some(1));
+    }
+
+    #[test]
+    fn test_parse_diff_combined_diff() {
+        let text = "\
+diff --cc src/lib.rs
+index 1111111,2222222..3333333
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@@ -1,2 -1,2 +1,3 @@@
+  fn shared() {}
+- fn ours() {}
++ fn theirs() {}
+++fn merged() {}
+diff --git a/other.rs b/other.rs
+";
+        let files = parse_diff(text);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].get_name(), "src/lib.rs");
+        assert_eq!(files[0].line_count(), 5);
+
+        let lines = &files[0].lines;
+        assert_eq!(lines[1].parent_markers, "  ");
+        assert_eq!(lines[1].content, "fn shared() {}");
+        assert_eq!(lines[2].line_type, LineType::Removed);
+        assert_eq!(lines[2].old_line_num, Some(2));
+        assert_eq!(lines[3].line_type, LineType::Added);
+        assert_eq!(lines[3].old_line_num, None);
+        assert_eq!(lines[3].new_line_num, Some(2));
+        assert_eq!(lines[4].parent_markers, "++");
+        assert_eq!(lines[4].old_line_num, None);
+        assert_eq!(lines[4].new_line_num, Some(3));
+    }
+
+    #[test]
+    fn test_parse_diff_plain_unified_diff() {
+        let text = "\
+--- old/config.toml\t2024-01-01 00:00:00
++++ new/config.toml\t2024-01-02 00:00:00
+@@ -1 +1 @@
+-a = 1
++a = 2
+--- gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let files = parse_diff(text);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].get_name(), "new/config.toml");
+        assert_eq!(files[0].line_count(), 3);
+        assert_eq!(files[1].get_name(), "gone.txt");
+        assert_eq!(files[1].get_status(), 'D');
+    }
+}
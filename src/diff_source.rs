@@ -0,0 +1,272 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result, bail};
+
+#[cfg(feature = "native-git")]
+use crate::git_native::NativeDiff;
+use crate::{
+    diff_file::DiffFile,
+    diff_parser::parse_diff,
+    file_diff::{diff_files, diff_paths},
+    git,
+    interdiff::interdiff,
+    range_diff::parse_range_diff,
+};
+
+/// Where the diff shown by the app comes from.
+#[derive(Clone)]
+pub enum DiffSource {
+    /// Run `git diff` with the given arguments.
+    Git(Vec<String>),
+    /// Read a patch from a file on disk.
+    PatchFile(PathBuf),
+    /// A patch that was read from standard input.
+    Stdin(String),
+    /// Diff two files directly, as invoked by `git difftool` or `GIT_EXTERNAL_DIFF`.
+    Files {
+        name: String,
+        old: PathBuf,
+        new: PathBuf,
+    },
+    /// Diff two files or directory trees outside of any repository.
+    NoIndex { old: PathBuf, new: PathBuf },
+    /// Compare two versions of a patch file by file.
+    Interdiff { old: PathBuf, new: PathBuf },
+    /// Run `git range-diff` with the given arguments, showing one entry per commit pairing.
+    RangeDiff(Vec<String>),
+    /// Build the diff with libgit2 instead of the git CLI.
+    #[cfg(feature = "native-git")]
+    Native(NativeDiff),
+}
+
+impl DiffSource {
+    /// Reads a patch from standard input.
+    ///
+    /// Stdin can only be consumed once, so the text is kept for reloading.
+    pub fn from_stdin() -> Result<Self> {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .context("failed to read patch from stdin")?;
+        Ok(DiffSource::Stdin(strip_ansi(&String::from_utf8_lossy(
+            &bytes,
+        ))))
+    }
+
+    /// Returns the source for the changes a single commit made to `paths` (or to every file
+    /// when empty).
+    pub fn for_commit(commit: &str, paths: &[String]) -> Result<Self> {
+        #[cfg(feature = "native-git")]
+        if let Some(native) = NativeDiff::from_args(false, Some(commit), &[], paths) {
+            return Ok(DiffSource::Native(native));
+        }
+
+        let mut args = git::commit_range(commit)?;
+        if !paths.is_empty() {
+            args.push("--".to_string());
+            args.extend(paths.iter().cloned());
+        }
+        Ok(DiffSource::Git(args))
+    }
+
+    /// Identifies the repository range this source compares, for state saved per range.
+    /// Returns `None` for sources outside of a repository.
+    pub fn range_key(&self) -> Option<String> {
+        match self {
+            DiffSource::Git(args) => Some(args.join(" ")),
+            DiffSource::RangeDiff(args) => Some(format!("range-diff {}", args.join(" "))),
+            #[cfg(feature = "native-git")]
+            DiffSource::Native(native) => Some(native.range_key()),
+            _ => None,
+        }
+    }
+
+    /// Returns the paths the diff is limited to when this source compares the working tree
+    /// against the index, where untracked files are worth showing too.
+    pub fn working_tree_paths(&self) -> Option<Vec<String>> {
+        match self {
+            DiffSource::Git(args) => {
+                let mut args = args.split(|arg| arg == "--");
+                let mut paths = Vec::new();
+                for arg in args.next().unwrap_or_default() {
+                    // Any revision or --cached means the working tree isn't being compared
+                    if arg == "--cached" || arg == "--staged" {
+                        return None;
+                    }
+                    if arg.starts_with('-') {
+                        continue;
+                    }
+                    // Like git, take an argument before `--` that isn't a revision as a path
+                    if arg.contains("..") || git::is_revision(arg) {
+                        return None;
+                    }
+                    paths.push(arg.clone());
+                }
+                paths.extend(args.flatten().cloned());
+                Some(paths)
+            }
+            #[cfg(feature = "native-git")]
+            DiffSource::Native(native) => native.working_tree_paths(),
+            _ => None,
+        }
+    }
+
+    /// Returns where the new version of the file `name` is on disk, for opening it in an
+    /// editor.
+    pub fn file_path(&self, name: &str) -> Result<PathBuf> {
+        match self {
+            // Names are relative to the compared directories, or the new file itself
+            DiffSource::NoIndex { new, .. } if new.is_dir() => Ok(new.join(name)),
+            DiffSource::NoIndex { new, .. } => Ok(new.clone()),
+            DiffSource::Interdiff { .. } | DiffSource::RangeDiff(_) => {
+                bail!("{name} isn't a file on disk")
+            }
+            // Diffs of the working tree name files relative to the repository root
+            _ if self.working_tree_paths().is_some() => {
+                let root = git::run(&["rev-parse", "--show-toplevel"])?;
+                Ok(PathBuf::from(root.trim()).join(name))
+            }
+            // Other versions, such as a commit's or the files git hands a difftool, aren't
+            // what is on disk
+            _ => bail!("{name} isn't a working tree file"),
+        }
+    }
+
+    /// Loads the changed files from this source.
+    pub fn load_files(&self) -> Result<Vec<DiffFile>> {
+        match self {
+            #[cfg(feature = "native-git")]
+            DiffSource::Native(native) => native.load_files(),
+            DiffSource::RangeDiff(_) => Ok(parse_range_diff(&self.read()?)),
+            DiffSource::Interdiff { old, new } => Ok(interdiff(
+                &DiffSource::PatchFile(old.clone()).load_files()?,
+                &DiffSource::PatchFile(new.clone()).load_files()?,
+            )),
+            _ => Ok(parse_diff(&self.read()?)),
+        }
+    }
+
+    /// Reads the unified diff text from this source.
+    fn read(&self) -> Result<String> {
+        match self {
+            DiffSource::Git(args) => {
+                git::ensure_repository()?;
+
+                let mut git_args = vec!["diff".to_string(), "--no-prefix".to_string()];
+                git_args.extend(args.iter().cloned());
+                git::run(&git_args)
+            }
+            DiffSource::RangeDiff(args) => {
+                git::ensure_repository()?;
+
+                let mut git_args = vec!["range-diff".to_string(), "--no-color".to_string()];
+                git_args.extend(args.iter().cloned());
+                git::run(&git_args)
+            }
+            DiffSource::PatchFile(path) => {
+                let bytes = fs::read(path)
+                    .with_context(|| format!("failed to read patch file {}", path.display()))?;
+                Ok(strip_ansi(&String::from_utf8_lossy(&bytes)))
+            }
+            DiffSource::Stdin(text) => Ok(text.clone()),
+            DiffSource::Files { name, old, new } => diff_files(name, old, new),
+            DiffSource::NoIndex { old, new } => diff_paths(old, new),
+            DiffSource::Interdiff { .. } => unreachable!("interdiffs are built from two patches"),
+            #[cfg(feature = "native-git")]
+            DiffSource::Native(_) => unreachable!("native diffs are not text based"),
+        }
+    }
+}
+
+/// Removes ANSI escape sequences, since git colours its output when piping to a pager.
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+
+        // Skip a CSI sequence "ESC [ params final", or just the character after a lone ESC
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[1mdiff --git a/x b/x\x1b[m\n\x1b[32m+added\x1b[m\n"),
+            "diff --git a/x b/x\n+added\n"
+        );
+        assert_eq!(strip_ansi("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_working_tree_paths() {
+        let git = |args: &[&str]| DiffSource::Git(args.iter().map(|arg| arg.to_string()).collect());
+
+        assert_eq!(git(&[]).working_tree_paths(), Some(vec![]));
+        assert_eq!(
+            git(&["-w", "--", "src"]).working_tree_paths(),
+            Some(vec!["src".to_string()])
+        );
+        assert_eq!(git(&["--cached"]).working_tree_paths(), None);
+        assert_eq!(git(&["HEAD~1", "--", "src"]).working_tree_paths(), None);
+        assert_eq!(git(&["main..HEAD"]).working_tree_paths(), None);
+        assert_eq!(
+            git(&["src/"]).working_tree_paths(),
+            Some(vec!["src/".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let no_index = DiffSource::NoIndex {
+            old: PathBuf::from("old"),
+            new: dir.path().to_path_buf(),
+        };
+        assert_eq!(
+            no_index.file_path("src/lib.rs").unwrap(),
+            dir.path().join("src/lib.rs")
+        );
+
+        let files = DiffSource::Files {
+            name: "src/lib.rs".to_string(),
+            old: PathBuf::from("/tmp/a"),
+            new: PathBuf::from("/tmp/b"),
+        };
+        assert!(files.file_path("src/lib.rs").is_err());
+
+        let range = DiffSource::Git(vec!["HEAD~3".to_string(), "HEAD".to_string()]);
+        assert!(range.file_path("src/lib.rs").is_err());
+        let patch = DiffSource::PatchFile(PathBuf::from("fix.patch"));
+        assert!(patch.file_path("src/lib.rs").is_err());
+
+        let working_tree = DiffSource::Git(vec!["--".to_string(), "src".to_string()]);
+        assert!(
+            working_tree
+                .file_path("src/lib.rs")
+                .unwrap()
+                .ends_with("src/lib.rs")
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    env, fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::temp_file::write_temp_file;
+
+/// Opens `path` in the user's editor (`$VISUAL`, then `$EDITOR`, then `vi`) and waits for it
+/// to exit. When `line` is given the editor is asked to jump to it, using the syntax that
+/// editor understands.
+pub fn open(path: &Path, line: Option<u32>) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // Run through the shell so that editors configured with arguments (e.g. "code --wait")
+    // work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$@\""))
+        .arg(&editor)
+        .args(file_args(&editor, &path.to_string_lossy(), line))
+        .stdin(Stdio::inherit())
+        .status()
+        .with_context(|| format!("failed to run {editor}"))?;
+
+    if !status.success() {
+        bail!("{editor} exited with {status}");
+    }
+
+    Ok(())
+}
+
+/// Builds the arguments that open `path` at `line` in `editor`. Most editors, including vi,
+/// Emacs and nano, take `+line` before the path; a few GUI editors take `path:line` instead.
+fn file_args(editor: &str, path: &str, line: Option<u32>) -> Vec<String> {
+    let Some(line) = line else {
+        return vec![path.to_string()];
+    };
+
+    let program = editor.split_whitespace().next().unwrap_or(editor);
+    let program = Path::new(program)
+        .file_name()
+        .map_or(program.into(), |name| name.to_string_lossy());
+    match program.as_ref() {
+        "code" | "code-insiders" | "codium" | "cursor" => {
+            vec!["--goto".to_string(), format!("{path}:{line}")]
+        }
+        "subl" | "hx" | "helix" | "zed" => vec![format!("{path}:{line}")],
+        _ => vec![format!("+{line}"), path.to_string()],
+    }
+}
+
+/// Lets the user edit `text` in their editor and returns the edited text.
+pub fn edit_text(text: &str) -> Result<String> {
+    let file = write_temp_file("edit", text)?;
+    open(file.path(), None)?;
+    Ok(fs::read_to_string(file.path())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_args() {
+        assert_eq!(
+            file_args("vim", "src/main.rs", Some(12)),
+            ["+12", "src/main.rs"]
+        );
+        assert_eq!(
+            file_args("/usr/bin/code --wait", "src/main.rs", Some(12)),
+            ["--goto", "src/main.rs:12"]
+        );
+        assert_eq!(file_args("hx", "src/main.rs", Some(3)), ["src/main.rs:3"]);
+        assert_eq!(file_args("nano", "notes.txt", None), ["notes.txt"]);
+    }
+}
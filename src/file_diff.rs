@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use similar::TextDiff;
+
+/// Diffs two files or two directory trees without involving git.
+///
+/// Directory trees are matched by relative path, so files only present on one side show up
+/// as added or deleted.
+pub fn diff_paths(old: &Path, new: &Path) -> Result<String> {
+    // Like git diff --no-index, only /dev/null may stand in for a missing side
+    for path in [old, new] {
+        if path != Path::new("/dev/null") && !path.exists() {
+            bail!("could not access {}", path.display());
+        }
+    }
+
+    match (old.is_dir(), new.is_dir()) {
+        (true, true) => diff_dirs(old, new),
+        (false, false) => diff_files(&new.to_string_lossy(), old, new),
+        _ => bail!(
+            "cannot compare a file with a directory: {} and {}",
+            old.display(),
+            new.display()
+        ),
+    }
+}
+
+fn diff_dirs(old: &Path, new: &Path) -> Result<String> {
+    let mut paths = BTreeSet::new();
+    collect_files(old, Path::new(""), &mut paths)?;
+    collect_files(new, Path::new(""), &mut paths)?;
+
+    let mut out = String::new();
+    for path in paths {
+        let name = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        out.push_str(&diff_files(&name, &old.join(&path), &new.join(&path))?);
+    }
+
+    Ok(out)
+}
+
+/// Recursively collects the paths of all files below `root`, relative to it.
+fn collect_files(root: &Path, relative: &Path, paths: &mut BTreeSet<PathBuf>) -> Result<()> {
+    let dir = root.join(relative);
+    let entries =
+        fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+
+        // Don't follow symlinked directories to avoid cycles
+        if entry.file_type()?.is_dir() {
+            if entry.file_name() != ".git" {
+                collect_files(root, &path, paths)?;
+            }
+        } else {
+            paths.insert(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Diffs two files on disk with `similar` and returns the result as a git-style unified diff
+/// labelled with `name`.
+///
+/// A missing file or `/dev/null` on either side is treated as empty, so the file shows up as
+/// added or deleted. Identical files produce no output.
+pub fn diff_files(name: &str, old: &Path, new: &Path) -> Result<String> {
+    let old_bytes = read_file(old)?;
+    let new_bytes = read_file(new)?;
+    if old_bytes == new_bytes {
+        return Ok(String::new());
+    }
+
+    let mut out = format!("diff --git a/{name} b/{name}\n");
+    let old_label = match old_bytes {
+        Some(_) => format!("a/{name}"),
+        None => "/dev/null".to_string(),
+    };
+    let new_label = match new_bytes {
+        Some(_) => format!("b/{name}"),
+        None => "/dev/null".to_string(),
+    };
+
+    let old_bytes = old_bytes.unwrap_or_default();
+    let new_bytes = new_bytes.unwrap_or_default();
+    if old_bytes.contains(&0) || new_bytes.contains(&0) {
+        if old_bytes != new_bytes {
+            out.push_str(&format!(
+                "Binary files {old_label} and {new_label} differ\n"
+            ));
+        }
+        return Ok(out);
+    }
+
+    let old_text = String::from_utf8_lossy(&old_bytes);
+    let new_text = String::from_utf8_lossy(&new_bytes);
+    let diff = TextDiff::from_lines(old_text.as_ref(), new_text.as_ref());
+    out.push_str(
+        &diff
+            .unified_diff()
+            .context_radius(3)
+            .header(&old_label, &new_label)
+            .to_string(),
+    );
+
+    Ok(out)
+}
+
+/// Reads a file, returning `None` for `/dev/null` or a file that does not exist.
+fn read_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    if path == Path::new("/dev/null") || !path.is_file() {
+        return Ok(None);
+    }
+
+    fs::read(path)
+        .map(Some)
+        .with_context(|| format!("failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_parser::parse_diff;
+
+    #[test]
+    fn test_diff_files_added_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let new = dir.path().join("new.rs");
+        fs::write(&new, "fn main() {}\n").unwrap();
+
+        let text = diff_files("src/main.rs", Path::new("/dev/null"), &new).unwrap();
+        let files = parse_diff(&text);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].get_name(), "src/main.rs");
+        assert_eq!(files[0].get_status(), 'A');
+        assert_eq!(files[0].lines[1].content, "fn main() {}");
+    }
+
+    #[test]
+    fn test_diff_paths_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old");
+        let new = dir.path().join("new");
+        fs::create_dir_all(old.join("src")).unwrap();
+        fs::create_dir_all(new.join("src")).unwrap();
+        fs::write(old.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(new.join("src/lib.rs"), "fn b() {}\n").unwrap();
+        fs::write(old.join("same.txt"), "same\n").unwrap();
+        fs::write(new.join("same.txt"), "same\n").unwrap();
+        fs::write(old.join("gone.txt"), "bye\n").unwrap();
+        fs::write(new.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let text = diff_paths(&old, &new).unwrap();
+        let files = parse_diff(&text);
+
+        let summary: Vec<_> = files
+            .iter()
+            .map(|file| (file.get_status(), file.get_name()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![('D', "gone.txt"), ('A', "main.rs"), ('M', "src/lib.rs")]
+        );
+        assert_eq!(files[1].lines[1].content, "fn main() {}");
+    }
+}
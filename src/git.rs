@@ -0,0 +1,75 @@
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+/// Runs git with the given arguments and returns its standard output.
+///
+/// A non-zero exit status is turned into an error carrying git's stderr.
+pub fn run<S: AsRef<std::ffi::OsStr>>(args: &[S]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to run git; is it installed and on your PATH?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let command = args
+            .iter()
+            .map(|arg| arg.as_ref().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        bail!("`git {}` failed: {}", command, stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Fails with git's own message when the current directory is not inside a repository.
+pub fn ensure_repository() -> Result<()> {
+    run(&["rev-parse", "--git-dir"])?;
+    Ok(())
+}
+
+/// Returns true if `rev` names an object of the repository in the current directory, which
+/// is how git tells a revision argument from a path.
+pub fn is_revision(rev: &str) -> bool {
+    Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{object}}"),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Builds the `git diff` revision arguments for the changes introduced by `commit`.
+///
+/// Root commits have no parent, so they are compared against the empty tree.
+pub fn commit_range(commit: &str) -> Result<Vec<String>> {
+    run(&["rev-parse", "--verify", &format!("{commit}^{{commit}}")])?;
+
+    let parent = format!("{commit}^");
+    let has_parent = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &parent])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("failed to run git; is it installed and on your PATH?")?
+        .success();
+
+    let base = if has_parent { parent } else { empty_tree()? };
+
+    Ok(vec![base, commit.to_string()])
+}
+
+/// Returns the object id of the empty tree for the current repository's hash algorithm.
+fn empty_tree() -> Result<String> {
+    Ok(run(&["hash-object", "-t", "tree", "--stdin"])?
+        .trim()
+        .to_string())
+}
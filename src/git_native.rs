@@ -0,0 +1,338 @@
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result, bail};
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Patch, Repository, Tree};
+
+use crate::diff_file::{DiffFile, DiffLine, LineType};
+
+/// Which two sides of the repository a native diff compares.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    /// Working tree against the index, like `git diff`.
+    IndexToWorkdir,
+    /// Index against `HEAD`, like `git diff --cached`.
+    HeadToIndex,
+    /// A revision against the working tree, like `git diff <rev>`.
+    TreeToWorkdir(String),
+    /// Two revisions, like `git diff <a> <b>` or `git diff <a>..<b>`.
+    TreeToTree(String, String),
+    /// The merge base of two revisions against the second, like `git diff <a>...<b>`.
+    MergeBaseToTree(String, String),
+    /// A commit against its first parent, or the empty tree for a root commit.
+    Commit(String),
+}
+
+/// A diff computed with libgit2 rather than by parsing `git diff` output.
+#[derive(Debug, Clone)]
+pub struct NativeDiff {
+    comparison: Comparison,
+    paths: Vec<String>,
+}
+
+impl NativeDiff {
+    /// Interprets diffscape's git arguments, returning `None` when they include options that
+    /// only the git CLI understands.
+    pub fn from_args(
+        staged: bool,
+        commit: Option<&str>,
+        diff_args: &[String],
+        paths: &[String],
+    ) -> Option<Self> {
+        let mut revisions = Vec::new();
+        let mut paths = paths.to_vec();
+        let mut args = diff_args.iter();
+        for arg in args.by_ref() {
+            match arg.as_str() {
+                "--" => break,
+                // --cached mixed with revisions is left to git, which knows how they combine
+                arg if arg.starts_with('-') => return None,
+                arg => revisions.push(arg.to_string()),
+            }
+        }
+        paths.extend(args.cloned());
+
+        let comparison = match (commit, staged, revisions.as_slice()) {
+            (Some(commit), false, []) => Comparison::Commit(commit.to_string()),
+            (None, true, []) => Comparison::HeadToIndex,
+            (None, false, []) => Comparison::IndexToWorkdir,
+            (None, false, [range]) => {
+                if let Some((base, head)) = range.split_once("...") {
+                    Comparison::MergeBaseToTree(or_head(base), or_head(head))
+                } else if let Some((base, head)) = range.split_once("..") {
+                    Comparison::TreeToTree(or_head(base), or_head(head))
+                } else if is_revision(range) {
+                    Comparison::TreeToWorkdir(range.clone())
+                } else {
+                    // git treats an argument that isn't a revision as a path
+                    return None;
+                }
+            }
+            (None, false, [base, head]) if is_revision(base) && is_revision(head) => {
+                Comparison::TreeToTree(base.clone(), head.clone())
+            }
+            _ => return None,
+        };
+
+        Some(Self { comparison, paths })
+    }
+
+    /// Computes the diff and converts each changed file into a `DiffFile`.
+    pub fn load_files(&self) -> Result<Vec<DiffFile>> {
+        let repo = Repository::discover(".").context("not a git repository")?;
+
+        let mut options = DiffOptions::new();
+        options.context_lines(3);
+        for path in &self.paths {
+            match repo_pathspec(&repo, path)? {
+                Some(pathspec) => options.pathspec(pathspec),
+                // The pathspec covers the whole repository
+                None => return self.without_paths().load_files(),
+            };
+        }
+
+        let mut diff = match &self.comparison {
+            Comparison::IndexToWorkdir => repo.diff_index_to_workdir(None, Some(&mut options))?,
+            Comparison::HeadToIndex => {
+                let head = head_tree(&repo)?;
+                repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?
+            }
+            Comparison::TreeToWorkdir(rev) => {
+                let tree = resolve_tree(&repo, rev)?;
+                repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?
+            }
+            Comparison::TreeToTree(base, head) => {
+                let base = resolve_tree(&repo, base)?;
+                let head = resolve_tree(&repo, head)?;
+                repo.diff_tree_to_tree(Some(&base), Some(&head), Some(&mut options))?
+            }
+            Comparison::MergeBaseToTree(base, head) => {
+                let base_id = resolve_commit_id(&repo, base)?;
+                let head_id = resolve_commit_id(&repo, head)?;
+                let merge_base = repo
+                    .merge_base(base_id, head_id)
+                    .with_context(|| format!("no merge base between {base} and {head}"))?;
+                let base = repo.find_commit(merge_base)?.tree()?;
+                let head = repo.find_commit(head_id)?.tree()?;
+                repo.diff_tree_to_tree(Some(&base), Some(&head), Some(&mut options))?
+            }
+            Comparison::Commit(rev) => {
+                let commit = repo
+                    .revparse_single(rev)
+                    .and_then(|object| object.peel_to_commit())
+                    .with_context(|| format!("unknown commit {rev}"))?;
+                let parent = match commit.parent(0) {
+                    Ok(parent) => Some(parent.tree()?),
+                    Err(_) => None,
+                };
+                repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), Some(&mut options))?
+            }
+        };
+
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+        diff_to_files(&diff)
+    }
+
+    /// Describes the comparison and paths, for state saved per range.
+    pub fn range_key(&self) -> String {
+        format!("{:?} {}", self.comparison, self.paths.join(" "))
+    }
+
+    /// Returns the paths the diff is limited to when it compares the working tree against
+    /// the index.
+    pub fn working_tree_paths(&self) -> Option<Vec<String>> {
+        matches!(self.comparison, Comparison::IndexToWorkdir).then(|| self.paths.clone())
+    }
+
+    fn without_paths(&self) -> Self {
+        Self {
+            comparison: self.comparison.clone(),
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// Converts a pathspec relative to the current directory, as git accepts it, into one
+/// relative to the repository root, as libgit2 expects. Returns `None` for the root itself.
+fn repo_pathspec(repo: &Repository, path: &str) -> Result<Option<String>> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(Some(path.to_string()));
+    };
+    let workdir = workdir.canonicalize()?;
+    let cwd = std::env::current_dir()?.canonicalize()?;
+    let prefix = cwd.strip_prefix(&workdir).unwrap_or(Path::new(""));
+
+    let mut components: Vec<String> = Vec::new();
+    for component in prefix.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                components.pop();
+            }
+            Component::Normal(part) => components.push(part.to_string_lossy().into_owned()),
+            _ => {}
+        }
+    }
+
+    if components.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(components.join("/")))
+    }
+}
+
+/// Returns true if `rev` names a revision of the repository in the current directory.
+fn is_revision(rev: &str) -> bool {
+    Repository::discover(".").is_ok_and(|repo| repo.revparse_single(rev).is_ok())
+}
+
+fn or_head(rev: &str) -> String {
+    if rev.is_empty() {
+        "HEAD".to_string()
+    } else {
+        rev.to_string()
+    }
+}
+
+/// Returns the tree of `HEAD`, or `None` on an unborn branch.
+fn head_tree(repo: &Repository) -> Result<Option<Tree<'_>>> {
+    match repo.head() {
+        Ok(head) => Ok(Some(head.peel_to_tree()?)),
+        Err(err) if err.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn resolve_tree<'r>(repo: &'r Repository, rev: &str) -> Result<Tree<'r>> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .with_context(|| format!("unknown revision {rev}"))
+}
+
+fn resolve_commit_id(repo: &Repository, rev: &str) -> Result<git2::Oid> {
+    Ok(repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("unknown commit {rev}"))?
+        .id())
+}
+
+fn diff_to_files(diff: &Diff) -> Result<Vec<DiffFile>> {
+    let mut files = Vec::new();
+
+    for (index, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().into_owned());
+        let Some(path) = path else {
+            bail!("diff entry without a path");
+        };
+
+        let mut file = DiffFile::new(&path);
+        file.set_status(match delta.status() {
+            Delta::Added | Delta::Untracked => 'A',
+            Delta::Deleted => 'D',
+            Delta::Renamed => 'R',
+            Delta::Copied => 'C',
+            Delta::Typechange => 'T',
+            Delta::Conflicted => 'U',
+            _ => 'M',
+        });
+        match delta.status() {
+            Delta::Added | Delta::Untracked => {
+                file.set_mode(&format!("{:o}", u32::from(delta.new_file().mode())));
+            }
+            Delta::Deleted => file.set_mode(&format!("{:o}", u32::from(delta.old_file().mode()))),
+            Delta::Renamed | Delta::Copied => {
+                if let Some(old_path) = delta.old_file().path() {
+                    file.set_old_name(&old_path.to_string_lossy());
+                }
+            }
+            _ => {}
+        }
+
+        // Binary detection happens while the patch is generated, so check the patch's delta
+        match Patch::from_diff(diff, index)? {
+            Some(patch) if !patch.delta().flags().is_binary() => {
+                add_patch_lines(&mut file, &patch)?
+            }
+            _ => file.add_line(DiffLine::new_header("Binary files differ")),
+        }
+
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+fn add_patch_lines(file: &mut DiffFile, patch: &Patch) -> Result<()> {
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)?;
+        file.add_line(DiffLine::new_header(
+            String::from_utf8_lossy(hunk.header()).trim_end(),
+        ));
+
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            let line_type = match line.origin() {
+                '+' => LineType::Added,
+                '-' => LineType::Removed,
+                ' ' => LineType::Context,
+                // The end-of-file markers note that the line before has no newline
+                '=' | '>' | '<' => {
+                    if let Some(last) = file.lines.last_mut() {
+                        last.no_newline = true;
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let content = String::from_utf8_lossy(line.content());
+
+            file.add_line(DiffLine {
+                line_type,
+                old_line_num: line.old_lineno(),
+                new_line_num: line.new_lineno(),
+                content: content.trim_end_matches(['\n', '\r']).to_string(),
+                parent_markers: String::new(),
+                no_newline: false,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let diff = NativeDiff::from_args(false, None, &[], &[]).unwrap();
+        assert_eq!(diff.comparison, Comparison::IndexToWorkdir);
+
+        let diff = NativeDiff::from_args(false, None, &args(&["main...feature"]), &[]).unwrap();
+        assert_eq!(
+            diff.comparison,
+            Comparison::MergeBaseToTree("main".to_string(), "feature".to_string())
+        );
+
+        let diff =
+            NativeDiff::from_args(false, None, &args(&["HEAD~1..", "--", "src"]), &[]).unwrap();
+        assert_eq!(
+            diff.comparison,
+            Comparison::TreeToTree("HEAD~1".to_string(), "HEAD".to_string())
+        );
+        assert_eq!(diff.paths, args(&["src"]));
+
+        assert!(NativeDiff::from_args(false, None, &args(&["-w"]), &[]).is_none());
+        assert!(NativeDiff::from_args(false, None, &args(&["--cached", "HEAD~1"]), &[]).is_none());
+        assert!(NativeDiff::from_args(false, None, &args(&["src/"]), &[]).is_none());
+    }
+}
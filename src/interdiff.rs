@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::diff_file::{DiffFile, DiffLine, LineType};
+
+/// Number of unchanged patch lines kept around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Compares two versions of a patch file by file, returning the diff between each file's
+/// patches.
+///
+/// The lines use the same nesting as `git range-diff`: the outer marker gives the line type
+/// and the content keeps the patch line's own marker. Hunk line numbers are dropped before
+/// comparing so that changes elsewhere in the file do not show up as noise. Files whose
+/// patches are identical are left out; files only in the old or new version are marked `D` or
+/// `A`.
+pub fn interdiff(old: &[DiffFile], new: &[DiffFile]) -> Vec<DiffFile> {
+    let names: BTreeSet<&str> = old.iter().chain(new).map(DiffFile::get_name).collect();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old_lines = file_patch(old, name);
+            let new_lines = file_patch(new, name);
+
+            let mut file = DiffFile::new(name);
+            match (&old_lines, &new_lines) {
+                (Some(_), None) => file.set_status('D'),
+                (None, Some(_)) => file.set_status('A'),
+                _ => {}
+            }
+            add_diff_lines(
+                &mut file,
+                &old_lines.unwrap_or_default(),
+                &new_lines.unwrap_or_default(),
+            );
+
+            (file.line_count() > 0).then_some(file)
+        })
+        .collect()
+}
+
+/// Collects the patch lines for `name`, which a patch series can touch in several commits.
+fn file_patch(files: &[DiffFile], name: &str) -> Option<Vec<String>> {
+    let patches: Vec<&DiffFile> = files
+        .iter()
+        .filter(|file| file.get_name() == name)
+        .collect();
+
+    (!patches.is_empty()).then(|| patches.into_iter().flat_map(patch_lines).collect())
+}
+
+/// Renders a file's patch as lines with their markers, without hunk line numbers.
+fn patch_lines(file: &DiffFile) -> Vec<String> {
+    file.lines
+        .iter()
+        .map(|line| match line.line_type {
+            LineType::Added => format!("+{}", line.content),
+            LineType::Removed => format!("-{}", line.content),
+            LineType::Context => format!(" {}", line.content),
+            LineType::Header => match line.content.strip_prefix("@@") {
+                // Keep any function context after the ranges
+                Some(rest) => match rest.split_once("@@") {
+                    Some((_, context)) => format!("@@{context}"),
+                    None => "@@".to_string(),
+                },
+                None => line.content.clone(),
+            },
+        })
+        .collect()
+}
+
+fn add_diff_lines(file: &mut DiffFile, old: &[String], new: &[String]) {
+    let old_refs: Vec<&str> = old.iter().map(String::as_str).collect();
+    let new_refs: Vec<&str> = new.iter().map(String::as_str).collect();
+    let diff = TextDiff::from_slices(&old_refs, &new_refs);
+
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        // Label each group with the hunk it starts in, for orientation
+        let start = group.first().map_or(0, |op| op.old_range().start);
+        let hunk = old[..start]
+            .iter()
+            .rev()
+            .find(|line| line.starts_with("@@"))
+            .map_or("@@", String::as_str);
+        file.add_line(DiffLine::new_header(hunk));
+
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let line_type = match change.tag() {
+                    ChangeTag::Equal => LineType::Context,
+                    ChangeTag::Delete => LineType::Removed,
+                    ChangeTag::Insert => LineType::Added,
+                };
+                file.add_line(DiffLine {
+                    line_type,
+                    old_line_num: None,
+                    new_line_num: None,
+                    content: change.value().to_string(),
+                    parent_markers: String::new(),
+                    no_newline: false,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_parser::parse_diff;
+
+    #[test]
+    fn test_interdiff() {
+        let v1 = parse_diff(
+            "\
+--- a/same.rs
++++ b/same.rs
+@@ -1 +1 @@ fn main
+-a
++b
+--- a/changed.rs
++++ b/changed.rs
+@@ -1 +1 @@
+-x
++y
+--- a/dropped.rs
++++ b/dropped.rs
+@@ -1 +1 @@
+-p
++q
+",
+        );
+        let v2 = parse_diff(
+            "\
+--- a/same.rs
++++ b/same.rs
+@@ -10 +10 @@ fn main
+-a
++b
+--- a/changed.rs
++++ b/changed.rs
+@@ -1 +1 @@
+-x
++z
+",
+        );
+        let files = interdiff(&v1, &v2);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].get_name(), "changed.rs");
+        assert_eq!(files[0].get_status(), 'M');
+        assert_eq!(files[0].lines[3].line_type, LineType::Removed);
+        assert_eq!(files[0].lines[3].content, "+y");
+        assert_eq!(files[0].lines[4].content, "+z");
+        assert_eq!(files[1].get_name(), "dropped.rs");
+        assert_eq!(files[1].get_status(), 'D');
+    }
+}
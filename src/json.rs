@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::{
+    diff_file::{DiffFile, LineType},
+    diff_parser::parse_hunk_header,
+    ui::diff_utils::compute_intra_line_diff,
+};
+
+/// The machine-readable form of a diff written by `--json`.
+#[derive(Serialize)]
+struct JsonDiff<'a> {
+    files: Vec<JsonFile<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonFile<'a> {
+    path: &'a str,
+    /// The path before a rename or copy.
+    old_path: Option<&'a str>,
+    status: char,
+    hunks: Vec<JsonHunk<'a>>,
+}
+
+/// A header line and the lines following it. The ranges are null for headers that aren't
+/// "@@" hunk headers, such as "Binary files ... differ".
+#[derive(Serialize)]
+struct JsonHunk<'a> {
+    header: Option<&'a str>,
+    old_start: Option<u32>,
+    old_lines: Option<u32>,
+    new_start: Option<u32>,
+    new_lines: Option<u32>,
+    lines: Vec<JsonLine<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    #[serde(rename = "type")]
+    line_type: &'a LineType,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+    content: &'a str,
+    /// Byte ranges of `content` that differ from the paired removed or added line, as
+    /// highlighted by the views.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<Range<usize>>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    parent_markers: &'a str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    no_newline: bool,
+}
+
+/// Serializes the parsed files as pretty-printed JSON.
+pub fn diff_to_json(files: &[DiffFile]) -> serde_json::Result<String> {
+    let diff = JsonDiff {
+        files: files.iter().map(json_file).collect(),
+    };
+    serde_json::to_string_pretty(&diff)
+}
+
+fn json_file(file: &DiffFile) -> JsonFile<'_> {
+    let mut hunks: Vec<JsonHunk> = Vec::new();
+
+    for (index, line) in file.lines.iter().enumerate() {
+        if line.line_type == LineType::Header {
+            let ranges = parse_hunk_header(&line.content);
+            hunks.push(JsonHunk {
+                header: Some(&line.content),
+                old_start: ranges.map(|((start, _), _)| start),
+                old_lines: ranges.map(|((_, count), _)| count),
+                new_start: ranges.map(|(_, (start, _))| start),
+                new_lines: ranges.map(|(_, (_, count))| count),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        // Like the views, only a removed line directly followed by an added one is compared
+        let previous = index.checked_sub(1).map(|i| &file.lines[i]);
+        let next = file.lines.get(index + 1);
+        let changes = match (&line.line_type, previous, next) {
+            (LineType::Removed, _, Some(next)) if next.line_type == LineType::Added => {
+                compute_intra_line_diff(&line.content, &next.content).0
+            }
+            (LineType::Added, Some(previous), _) if previous.line_type == LineType::Removed => {
+                compute_intra_line_diff(&previous.content, &line.content).1
+            }
+            _ => Vec::new(),
+        };
+
+        if hunks.is_empty() {
+            hunks.push(JsonHunk {
+                header: None,
+                old_start: None,
+                old_lines: None,
+                new_start: None,
+                new_lines: None,
+                lines: Vec::new(),
+            });
+        }
+        hunks.last_mut().unwrap().lines.push(JsonLine {
+            line_type: &line.line_type,
+            old_line: line.old_line_num,
+            new_line: line.new_line_num,
+            content: &line.content,
+            changes,
+            parent_markers: &line.parent_markers,
+            no_newline: line.no_newline,
+        });
+    }
+
+    JsonFile {
+        path: file.get_name(),
+        old_path: file.get_old_name(),
+        status: file.get_status(),
+        hunks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_parser::parse_diff;
+
+    #[test]
+    fn test_diff_to_json() {
+        let files = parse_diff(
+            "diff --git a/src/lib.rs b/src/lib.rs\n\
+             --- a/src/lib.rs\n\
+             +++ b/src/lib.rs\n\
+             @@ -3,2 +3,2 @@ fn main\n \
+             keep\n\
+             -let x = 1;\n\
+             +let x = 2;\n",
+        );
+        let json: serde_json::Value = serde_json::from_str(&diff_to_json(&files).unwrap()).unwrap();
+
+        let file = &json["files"][0];
+        assert_eq!(file["path"], "src/lib.rs");
+        assert_eq!(file["old_path"], serde_json::Value::Null);
+        assert_eq!(file["status"], "M");
+
+        let hunk = &file["hunks"][0];
+        assert_eq!(hunk["old_start"], 3);
+        assert_eq!(hunk["new_lines"], 2);
+        assert_eq!(hunk["lines"][0]["type"], "context");
+        assert_eq!(hunk["lines"][0]["old_line"], 3);
+        assert_eq!(hunk["lines"][1]["type"], "removed");
+        assert_eq!(hunk["lines"][1]["new_line"], serde_json::Value::Null);
+        assert_eq!(
+            hunk["lines"][2]["changes"],
+            serde_json::json!([{ "start": 8, "end": 9 }])
+        );
+        assert!(hunk["lines"][0].get("changes").is_none());
+    }
+}
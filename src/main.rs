@@ -1,61 +1,417 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result, bail};
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    },
 };
 use ratatui::{
     Terminal,
     backend::{Backend, CrosstermBackend},
 };
-use std::io;
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use crate::app::{Action, App};
-use crate::ui::render_ui::ui;
+use crate::app::{Action, App, View, YankTarget};
+use crate::commit_log::{CommitDetails, CommitLog};
+use crate::commit_steps::CommitSteps;
+use crate::conflicts::{ConflictFile, Resolution};
+use crate::diff_source::DiffSource;
+use crate::json::diff_to_json;
+use crate::review::Review;
+use crate::status::Status;
+use crate::ui::{html::write_html, render_ui::ui};
+use crate::watch::WorkTreeWatcher;
 
 mod app;
+mod clipboard;
+mod comments;
+mod commit_log;
+mod commit_steps;
+mod conflicts;
 mod diff_file;
+mod diff_parser;
+mod diff_source;
+mod editor;
+mod file_diff;
+mod git;
+#[cfg(feature = "native-git")]
+mod git_native;
+mod interdiff;
+mod json;
+mod pager;
+mod patch;
+mod range_diff;
+mod review;
+mod state;
+mod status;
+mod temp_file;
 mod ui;
+mod untracked;
+mod viewed;
+mod watch;
+
+/// How often `--watch` checks the working tree for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Diff the staged files.
     #[arg(long)]
     staged: bool,
 
     /// Diff the given commit.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "staged")]
     commit: Option<String>,
 
-    /// Git diff arguments (e.g., "HEAD~1", "main..feature")
-    #[arg(default_value = "")]
-    diff_args: String,
+    /// Compare two files or directories outside of any git repository.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["staged", "commit", "patch"]
+    )]
+    no_index: Option<Vec<PathBuf>>,
+
+    /// Step through the commits of the given range one at a time.
+    #[arg(long, conflicts_with_all = ["staged", "commit", "patch", "no_index"])]
+    step: bool,
+
+    /// Reload the diff whenever the working tree or index changes.
+    #[arg(long, conflicts_with_all = ["patch", "no_index"])]
+    watch: bool,
+
+    /// Write the diff to FILE as a self-contained HTML page instead of starting the TUI.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["step", "watch", "pager"])]
+    html: Option<PathBuf>,
+
+    /// Print the parsed diff as JSON instead of starting the TUI.
+    #[arg(long, conflicts_with_all = ["html", "step", "watch", "pager"])]
+    json: bool,
+
+    /// Print the diff as coloured text instead of starting the TUI (implied when stdout is
+    /// not a terminal).
+    #[arg(long)]
+    pager: bool,
+
+    /// View a patch file instead of running git diff ("-" reads from stdin).
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["staged", "commit"])]
+    patch: Option<PathBuf>,
+
+    /// Arguments passed verbatim to git diff (e.g., "HEAD~1", "main..feature", "--cached")
+    #[arg(allow_hyphen_values = true)]
+    diff_args: Vec<String>,
+
+    /// Limit the diff to the given paths.
+    #[arg(last = true)]
+    paths: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Browse the commit history and view the diff of each commit.
+    Log {
+        /// The revision range to list (defaults to the current branch).
+        range: Option<String>,
+
+        /// Only list commits touching the given paths.
+        #[arg(last = true)]
+        paths: Vec<String>,
+    },
+    /// Review a branch like a pull request: diff it against its merge base with BASE.
+    Review {
+        /// The branch the changes will be merged into.
+        base: String,
+
+        /// The branch to review (defaults to HEAD).
+        head: Option<String>,
+
+        /// Limit the diff to the given paths.
+        #[arg(last = true)]
+        paths: Vec<String>,
+    },
+    /// Compare two versions of a branch commit by commit, as `git range-diff` does.
+    RangeDiff {
+        /// Arguments passed to git range-diff (e.g., "main old-branch new-branch" or
+        /// "feature@{1}...feature").
+        #[arg(required = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Show staged and unstaged changes together, and stage or unstage hunks.
+    Status {
+        /// Limit the changes to the given paths.
+        #[arg(last = true)]
+        paths: Vec<String>,
+    },
+    /// Resolve merge conflicts: pick ours, theirs or both for each conflict, or edit it.
+    Conflicts,
+    /// Show what changed between two versions of a patch.
+    Interdiff {
+        /// The earlier version of the patch.
+        old: PathBuf,
+
+        /// The later version of the patch.
+        new: PathBuf,
+    },
+}
+
+impl Args {
+    /// Loads the initial content of the app for the requested mode.
+    fn load(&self, app: &mut App) -> Result<()> {
+        match &self.command {
+            Some(Commands::Log { range, paths }) => {
+                app.show_log(CommitLog::new(range.clone(), paths.clone())?);
+                Ok(())
+            }
+            Some(Commands::Review { base, head, paths }) => {
+                let review = Review::new(base, head.as_deref(), paths.clone())?;
+                app.load_diff(&review.source())?;
+                app.review = Some(review);
+                Ok(())
+            }
+            Some(Commands::Status { paths }) => app.load_status(Status::new(paths.clone())),
+            Some(Commands::Conflicts) => {
+                let (conflicts, unsupported) = ConflictFile::load_all()?;
+                app.show_conflicts(conflicts);
+                if !unsupported.is_empty() {
+                    app.show_message(format!(
+                        "Resolve these with git instead: {}",
+                        unsupported.join(", ")
+                    ));
+                }
+                Ok(())
+            }
+            Some(Commands::RangeDiff { .. } | Commands::Interdiff { .. }) => {
+                app.load_diff(&self.diff_source()?)
+            }
+            None if self.step => {
+                let (range, options, paths) = self.step_range()?;
+                let steps = CommitSteps::new(&range, options, paths)?;
+                app.start_steps(steps)
+            }
+            None => {
+                app.load_diff(&self.diff_source()?)?;
+                if let Some(commit) = &self.commit {
+                    app.commit_details = Some(CommitDetails::load(commit)?);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Determines where to read the diff from.
+    ///
+    /// A patch piped into stdin is used when no git arguments were given, unless stdin is
+    /// empty.
+    fn diff_source(&self) -> Result<DiffSource> {
+        if let Some(Commands::Review { base, head, paths }) = &self.command {
+            return Ok(Review::new(base, head.as_deref(), paths.clone())?.source());
+        }
+        if let Some(Commands::Conflicts) = &self.command {
+            bail!("the conflict resolver needs a terminal");
+        }
+        if let Some(Commands::Status { .. }) = &self.command {
+            bail!("the status view needs a terminal");
+        }
+        if let Some(Commands::RangeDiff { args }) = &self.command {
+            return Ok(DiffSource::RangeDiff(args.clone()));
+        }
+        if let Some(Commands::Interdiff { old, new }) = &self.command {
+            return Ok(DiffSource::Interdiff {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+
+        if let Some([old, new]) = self.no_index.as_deref() {
+            return Ok(DiffSource::NoIndex {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+
+        if let Some(patch) = &self.patch {
+            if patch.as_os_str() == "-" {
+                return DiffSource::from_stdin();
+            }
+            return Ok(DiffSource::PatchFile(patch.clone()));
+        }
+
+        if let Some(source) = self.external_diff_source() {
+            return Ok(source);
+        }
+
+        let has_git_args = self.staged
+            || self.commit.is_some()
+            || !self.diff_args.is_empty()
+            || !self.paths.is_empty();
+        if !has_git_args && !io::stdin().is_terminal() {
+            let source = DiffSource::from_stdin()?;
+            // Scripts and CI jobs often run with an empty stdin such as /dev/null, which
+            // means nothing was piped rather than an empty patch
+            if !matches!(&source, DiffSource::Stdin(text) if text.trim().is_empty()) {
+                return Ok(source);
+            }
+        }
+
+        #[cfg(feature = "native-git")]
+        if let Some(native) = git_native::NativeDiff::from_args(
+            self.staged,
+            self.commit.as_deref(),
+            &self.diff_args,
+            &self.paths,
+        ) {
+            return Ok(DiffSource::Native(native));
+        }
+
+        Ok(DiffSource::Git(self.git_diff_args()?))
+    }
+
+    /// Recognises the calling conventions of `GIT_EXTERNAL_DIFF`
+    /// (`path old-file old-hex old-mode new-file new-hex new-mode`) and `git difftool -x`
+    /// (`LOCAL REMOTE`).
+    fn external_diff_source(&self) -> Option<DiffSource> {
+        if self.staged || self.commit.is_some() || !self.paths.is_empty() {
+            return None;
+        }
+
+        match self.diff_args.as_slice() {
+            [path, old, _, old_mode, new, _, new_mode]
+                if is_file_mode(old_mode) && is_file_mode(new_mode) =>
+            {
+                Some(DiffSource::Files {
+                    name: path.clone(),
+                    old: PathBuf::from(old),
+                    new: PathBuf::from(new),
+                })
+            }
+            // Two plain arguments are paths to limit git diff to, unless difftool ran us
+            [local, remote]
+                if run_by_difftool() && is_diffable_file(local) && is_diffable_file(remote) =>
+            {
+                // difftool exports the path being compared as $BASE for external commands
+                let name = env::var("BASE").unwrap_or_else(|_| remote.clone());
+                Some(DiffSource::Files {
+                    name,
+                    old: PathBuf::from(local),
+                    new: PathBuf::from(remote),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts the revision arguments into a range for `git rev-list`, along with the diff
+    /// options given before `--` and any paths.
+    ///
+    /// `A B` is treated as `A..B` and a single revision `A` as `A..HEAD`; `A...B` is kept so
+    /// that the whole-range diff starts from the merge base.
+    fn step_range(&self) -> Result<(String, Vec<String>, Vec<String>)> {
+        let mut revisions = Vec::new();
+        let mut options = Vec::new();
+        let mut paths = self.paths.clone();
+        let mut args = self.diff_args.iter();
+        for arg in args.by_ref() {
+            if arg == "--" {
+                break;
+            }
+            if arg.starts_with('-') {
+                options.push(arg.clone());
+            } else {
+                revisions.push(arg.as_str());
+            }
+        }
+        paths.extend(args.cloned());
+
+        let range = match revisions.as_slice() {
+            [range] if range.contains("..") => range.to_string(),
+            [base] => format!("{base}..HEAD"),
+            [base, head] => format!("{base}..{head}"),
+            _ => bail!("--step needs a revision range such as main..feature"),
+        };
+
+        Ok((range, options, paths))
+    }
+
+    /// Builds the full argument list to pass to `git diff`.
+    fn git_diff_args(&self) -> Result<Vec<String>> {
+        let mut diff_args = if let Some(commit) = &self.commit {
+            git::commit_range(commit)?
+        } else if self.staged {
+            vec!["--cached".to_string()]
+        } else {
+            Vec::new()
+        };
+        diff_args.extend(self.diff_args.iter().cloned());
+
+        if !self.paths.is_empty() {
+            diff_args.push("--".to_string());
+            diff_args.extend(self.paths.iter().cloned());
+        }
+
+        Ok(diff_args)
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    if let Some(path) = &args.html {
+        return write_html_file(&args, path).or_else(|err| exit_with_error(err));
+    }
+    if args.json {
+        return print_json(&args).or_else(|err| exit_with_error(err));
+    }
+
+    let is_log = matches!(args.command, Some(Commands::Log { .. }));
+    if !is_log && (args.pager || !io::stdout().is_terminal()) {
+        let source = args
+            .diff_source()
+            .unwrap_or_else(|err| exit_with_error(err));
+        let mut app = App::new(false);
+        if let Err(err) = app.load_diff(&source) {
+            exit_with_error(err);
+        }
+        let passthrough = match &source {
+            DiffSource::Stdin(text) => text.as_str(),
+            _ => "",
+        };
+        return pager::page(&app, passthrough);
+    }
+
+    let (width, _) = terminal::size()?;
+
+    // Enable side-by-side view by default if terminal is wide enough
+    let mut app = App::new(width >= 100);
+    app.find_untracked = true;
+
+    // Load the diff before taking over the terminal so git errors are printed normally.
+    // When the patch comes from stdin, crossterm reads keys from /dev/tty instead.
+    if let Err(err) = args.load(&mut app) {
+        exit_with_error(err);
+    }
+    let watcher = args
+        .watch
+        .then(|| WorkTreeWatcher::new().unwrap_or_else(|err| exit_with_error(err)));
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let width = terminal.size()?.width;
 
-    // Enable side-by-side view by default if terminal is wide enough
-    let mut app = App::new(width >= 100);
-    let diff_args = if let Some(commit) = args.commit {
-        format!("{}^..{}", &commit, &commit)
-    } else if args.staged {
-        "--cached".to_string()
-    } else {
-        args.diff_args
-    };
-    app.load_diff(&diff_args)?;
-
-    let res = run_app(&mut terminal, app);
+    let res = run_app(&mut terminal, app, watcher);
 
     disable_raw_mode()?;
     execute!(
@@ -72,11 +428,105 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
+/// Loads the diff and writes it to `path` as an HTML page.
+fn write_html_file(args: &Args, path: &Path) -> Result<()> {
+    let source = args.diff_source()?;
+    let mut app = App::new(false);
+    app.load_diff(&source)?;
+
+    let title = source
+        .range_key()
+        .filter(|key| !key.is_empty())
+        .unwrap_or_else(|| "working tree".to_string());
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    write_html(&mut out, &app, &title)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Loads the diff and prints it to stdout as JSON.
+fn print_json(args: &Args) -> Result<()> {
+    let mut app = App::new(false);
+    app.load_diff(&args.diff_source()?)?;
+
+    println!("{}", diff_to_json(&app.files)?);
+    Ok(())
+}
+
+/// Returns true for a git file mode such as "100644", or "." for a missing file.
+fn is_file_mode(mode: &str) -> bool {
+    mode == "." || (mode.len() == 6 && mode.chars().all(|c| c.is_digit(8)))
+}
+
+/// Returns true when running as a `git difftool` command, which sets the counters of the path
+/// being compared.
+fn run_by_difftool() -> bool {
+    env::var_os("GIT_DIFF_PATH_TOTAL").is_some() && env::var_os("GIT_DIFF_PATH_COUNTER").is_some()
+}
+
+/// Returns true if `path` is a regular file or `/dev/null`.
+fn is_diffable_file(path: &str) -> bool {
+    path == "/dev/null" || Path::new(path).is_file()
+}
+
+fn exit_with_error(err: anyhow::Error) -> ! {
+    eprintln!("error: {err:#}");
+    std::process::exit(1);
+}
+
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    mut watcher: Option<WorkTreeWatcher>,
+) -> Result<()> {
     while app.running {
         terminal.draw(|f| ui(f, &app))?;
 
+        // Wake up without a key press to clear transient messages and check for changes
+        let timeout = [
+            app.status_time_left(),
+            watcher.as_ref().map(|_| WATCH_INTERVAL),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        if let Some(timeout) = timeout
+            && !event::poll(timeout)?
+        {
+            app.expire_status_message();
+            // A pending comment or yank holds the reload until it is finished
+            if let Some(watcher) = watcher.as_mut()
+                && app.view == View::Diff
+                && app.comment_draft.is_none()
+                && !app.yank_pending
+                && watcher.changed()
+            {
+                app.perform_action(Action::Reload);
+            }
+            continue;
+        }
+
         if let Event::Key(key) = event::read()? {
+            if app.view == View::Log {
+                handle_log_key(&mut app, key.code);
+                continue;
+            }
+            if app.view == View::Conflicts {
+                handle_conflict_key(terminal, &mut app, key.code)?;
+                continue;
+            }
+            if app.comment_draft.is_some() {
+                handle_comment_key(&mut app, key.code);
+                continue;
+            }
+            if app.yank_pending {
+                handle_yank_key(&mut app, key.code);
+                continue;
+            }
+
             match key.code {
                 KeyCode::Char('q') => app.perform_action(Action::Quit),
                 KeyCode::Char('j') | KeyCode::Down => app.perform_action(Action::NextFile),
@@ -102,6 +552,24 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
                 }
                 KeyCode::Char('H') => app.perform_action(Action::ScrollLeft { amount: 10 }),
                 KeyCode::Char('L') => app.perform_action(Action::ScrollRight { amount: 10 }),
+                KeyCode::Esc | KeyCode::Backspace => app.perform_action(Action::ShowLog),
+                KeyCode::Char('c') => app.perform_action(Action::ToggleCommitHeader),
+                KeyCode::Char(']') => app.perform_action(Action::NextStep),
+                KeyCode::Char('[') => app.perform_action(Action::PrevStep),
+                KeyCode::Char('a') => app.perform_action(Action::ShowRangeDiff),
+                KeyCode::Char('v') => app.perform_action(Action::ToggleViewed),
+                KeyCode::Char('n') => app.perform_action(Action::NextUnviewed),
+                KeyCode::Char('U') => app.perform_action(Action::ToggleUntracked),
+                KeyCode::Char('S') => app.perform_action(Action::ToggleStaged),
+                KeyCode::Char('r') => app.perform_action(Action::Reload),
+                KeyCode::Char('e') => open_in_editor(terminal, &mut app)?,
+                KeyCode::Char('y') => app.perform_action(Action::StartYank),
+                KeyCode::Char('V') => app.perform_action(Action::ToggleLineMark),
+                KeyCode::Char(' ') => app.perform_action(Action::ToggleHunkSelection),
+                KeyCode::Char('P') => app.perform_action(Action::ExportHunks { message: None }),
+                KeyCode::Char('F') => export_format_patch(terminal, &mut app)?,
+                KeyCode::Char('C') => app.perform_action(Action::StartComment),
+                KeyCode::Char('X') => app.perform_action(Action::ExportComments),
                 _ => {}
             }
         }
@@ -109,3 +577,147 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
 
     Ok(())
 }
+
+fn handle_log_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Char('q') => app.perform_action(Action::Quit),
+        KeyCode::Char('j') | KeyCode::Down => app.perform_action(Action::NextCommit { amount: 1 }),
+        KeyCode::Char('k') | KeyCode::Up => app.perform_action(Action::PrevCommit { amount: 1 }),
+        KeyCode::Char('d') | KeyCode::PageDown => {
+            app.perform_action(Action::NextCommit { amount: 10 })
+        }
+        KeyCode::Char('u') | KeyCode::PageUp => {
+            app.perform_action(Action::PrevCommit { amount: 10 })
+        }
+        KeyCode::Char('g') | KeyCode::Home => app.perform_action(Action::FirstCommit),
+        KeyCode::Char('G') | KeyCode::End => app.perform_action(Action::LastCommit),
+        KeyCode::Enter => app.perform_action(Action::OpenCommit),
+        KeyCode::Char('?') => app.perform_action(Action::Help),
+        _ => {}
+    }
+}
+
+fn handle_comment_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Enter => app.perform_action(Action::SaveComment),
+        KeyCode::Esc => app.perform_action(Action::CancelComment),
+        KeyCode::Backspace => app.perform_action(Action::CommentBackspace),
+        KeyCode::Char(c) => app.perform_action(Action::CommentChar { c }),
+        _ => {}
+    }
+}
+
+fn handle_yank_key(app: &mut App, code: KeyCode) {
+    let target = match code {
+        KeyCode::Char('p') => YankTarget::Path,
+        KeyCode::Char('h') => YankTarget::Hunk,
+        KeyCode::Char('l') => YankTarget::Lines,
+        _ => return app.perform_action(Action::CancelYank),
+    };
+    app.perform_action(Action::Yank { target });
+}
+
+fn handle_conflict_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    code: KeyCode,
+) -> Result<()> {
+    match code {
+        KeyCode::Char('q') => app.perform_action(Action::Quit),
+        KeyCode::Char('j') | KeyCode::Down => app.perform_action(Action::NextConflictFile),
+        KeyCode::Char('k') | KeyCode::Up => app.perform_action(Action::PrevConflictFile),
+        KeyCode::Char('n') => app.perform_action(Action::NextConflict),
+        KeyCode::Char('p') => app.perform_action(Action::PrevConflict),
+        KeyCode::Char('o') => app.perform_action(Action::Resolve {
+            resolution: Some(Resolution::Ours),
+        }),
+        KeyCode::Char('t') => app.perform_action(Action::Resolve {
+            resolution: Some(Resolution::Theirs),
+        }),
+        KeyCode::Char('b') => app.perform_action(Action::Resolve {
+            resolution: Some(Resolution::Both),
+        }),
+        KeyCode::Char('x') => app.perform_action(Action::Resolve { resolution: None }),
+        KeyCode::Char('e') => {
+            let Some(region) = app.current_region() else {
+                return Ok(());
+            };
+            let text = region.result_lines().join("\n") + "\n";
+            match suspend_tui(terminal, || editor::edit_text(&text))? {
+                Ok(edited) => app.perform_action(Action::Resolve {
+                    resolution: Some(Resolution::Edited(
+                        edited.lines().map(str::to_string).collect(),
+                    )),
+                }),
+                Err(err) => app.show_message(format!("{err:#}")),
+            }
+        }
+        KeyCode::Char('w') => app.perform_action(Action::WriteResolved),
+        KeyCode::Char('?') => app.perform_action(Action::Help),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Opens the selected file in the user's editor at the line at the top of the diff pane,
+/// then reloads the diff to pick up the edits.
+fn open_in_editor<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    let (path, line) = match app.editor_target() {
+        Ok(target) => target,
+        Err(err) => {
+            app.show_message(format!("{err:#}"));
+            return Ok(());
+        }
+    };
+
+    match suspend_tui(terminal, || editor::open(&path, Some(line)))? {
+        Ok(()) => app.perform_action(Action::Reload),
+        Err(err) => app.show_message(format!("{err:#}")),
+    }
+
+    Ok(())
+}
+
+/// Asks for a commit message in the user's editor, then exports the selected hunks as a
+/// `git format-patch` style patch.
+fn export_format_patch<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    if app.selected_hunks.is_empty() {
+        app.perform_action(Action::ExportHunks { message: None });
+        return Ok(());
+    }
+
+    let template =
+        "\n# Enter the commit message for the patch. Lines starting with '#' are ignored.\n";
+    match suspend_tui(terminal, || editor::edit_text(template))? {
+        Ok(text) => {
+            let message: Vec<&str> = text.lines().filter(|line| !line.starts_with('#')).collect();
+            let message = message.join("\n");
+            if message.trim().is_empty() {
+                app.show_message("Not exported: the commit message is empty".to_string());
+            } else {
+                app.perform_action(Action::ExportHunks {
+                    message: Some(message),
+                });
+            }
+        }
+        Err(err) => app.show_message(format!("{err:#}")),
+    }
+
+    Ok(())
+}
+
+/// Hands the terminal to a child process, such as an editor, while `run` executes, then
+/// restores the TUI.
+fn suspend_tui<B: Backend, T>(terminal: &mut Terminal<B>, run: impl FnOnce() -> T) -> Result<T> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+    let result = run();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    Ok(result)
+}
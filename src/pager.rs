@@ -0,0 +1,215 @@
+use std::{
+    io::{self, BufWriter, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+
+use crate::{
+    app::App,
+    diff_parser::{parse_diff, parse_hunk_header},
+    ui::ansi::{write_ansi_diff, write_ansi_files},
+};
+
+/// Prints the loaded diff as ANSI-coloured text, paging through `less` when stdout is a
+/// terminal.
+///
+/// `passthrough` is the text piped in, if any: its file diffs are rendered and everything
+/// else, such as commit headers and messages or all of plain `git log`, is printed unchanged.
+pub fn page(app: &App, passthrough: &str) -> Result<()> {
+    let stdout = io::stdout();
+
+    if stdout.is_terminal()
+        && let Ok(mut less) = Command::new("less")
+            .args(["-R", "-F", "-X"])
+            .stdin(Stdio::piped())
+            .spawn()
+    {
+        if let Some(mut input) = less.stdin.take() {
+            ignore_broken_pipe(write_output(&mut input, app, passthrough))?;
+        }
+        less.wait()?;
+        return Ok(());
+    }
+
+    let mut out = BufWriter::new(stdout.lock());
+    ignore_broken_pipe(write_output(&mut out, app, passthrough).and_then(|_| out.flush()))
+}
+
+fn write_output<W: Write>(out: &mut W, app: &App, passthrough: &str) -> io::Result<()> {
+    if passthrough.is_empty() {
+        return write_ansi_diff(out, app);
+    }
+
+    // Like delta, keep the commit headers and messages of `git show` or `git log -p` in place
+    // and only render the file diffs between them
+    for segment in split_diffs(passthrough) {
+        match segment {
+            Segment::Text(text) => out.write_all(text.as_bytes())?,
+            Segment::Diff(diff) => write_ansi_files(out, &parse_diff(diff), app)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A stretch of pager input: either plain text or one or more file diffs.
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Diff(&'a str),
+}
+
+/// Splits `text` into the file diffs and the text around them, keeping every byte.
+///
+/// Hunk line counts decide where a diff ends, so indented commit messages that follow it
+/// aren't mistaken for context lines.
+fn split_diffs(text: &str) -> Vec<Segment<'_>> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut kinds = Vec::with_capacity(lines.len());
+    let mut in_diff = false;
+    let mut combined = false;
+    let (mut old_remaining, mut new_remaining) = (0u32, 0u32);
+
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let is_diff = if in_diff && (old_remaining > 0 || new_remaining > 0) {
+            match line.chars().next() {
+                Some('+') => new_remaining = new_remaining.saturating_sub(1),
+                Some('-') => old_remaining = old_remaining.saturating_sub(1),
+                Some('\\') => {}
+                _ => {
+                    old_remaining = old_remaining.saturating_sub(1);
+                    new_remaining = new_remaining.saturating_sub(1);
+                }
+            }
+            true
+        } else if line.starts_with("diff --git ")
+            || line.starts_with("diff --cc ")
+            || line.starts_with("diff --combined ")
+        {
+            combined = !line.starts_with("diff --git ");
+            true
+        } else if in_diff && line.starts_with("@@") {
+            if let Some(((_, old), (_, new))) = parse_hunk_header(line) {
+                (old_remaining, new_remaining) = (old, new);
+            }
+            true
+        } else if in_diff && combined {
+            // Combined hunks have a marker column per parent, so their lines are told apart by
+            // those markers rather than counted
+            line.starts_with(['+', '-', ' '])
+                || DIFF_HEADERS.iter().any(|header| line.starts_with(header))
+        } else if in_diff {
+            DIFF_HEADERS.iter().any(|header| line.starts_with(header))
+        } else {
+            // A plain unified diff starts with its "---" and "+++" lines
+            line.starts_with("--- ")
+                && lines
+                    .get(index + 1)
+                    .is_some_and(|next| next.starts_with("+++ "))
+        };
+        in_diff = is_diff;
+        if !is_diff {
+            combined = false;
+        }
+        kinds.push(is_diff);
+    }
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 && kinds[index] != kinds[index - 1] {
+            segments.push(segment(kinds[index - 1], &text[start..offset]));
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if start < text.len() {
+        segments.push(segment(kinds[kinds.len() - 1], &text[start..]));
+    }
+
+    segments
+}
+
+/// Lines that may appear between the start of a file diff and its first hunk, or instead of
+/// hunks.
+const DIFF_HEADERS: &[&str] = &[
+    "index ",
+    "old mode ",
+    "new mode ",
+    "new file mode ",
+    "deleted file mode ",
+    "similarity index ",
+    "dissimilarity index ",
+    "rename from ",
+    "rename to ",
+    "copy from ",
+    "copy to ",
+    "--- ",
+    "+++ ",
+    "Binary files ",
+    "@@",
+    "\\",
+];
+
+fn segment(is_diff: bool, text: &str) -> Segment<'_> {
+    if is_diff {
+        Segment::Diff(text)
+    } else {
+        Segment::Text(text)
+    }
+}
+
+/// Treats the reader going away (e.g. quitting `less` early) as success.
+fn ignore_broken_pipe(result: io::Result<()>) -> Result<()> {
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_diffs() {
+        let show = "\
+commit 1234567
+Author: A U Thor <author@example.com>
+
+    Fix the parser
+
+    - handle empty lines
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+ keep
+-old
++new
+
+commit 89abcde
+";
+        let segments = split_diffs(show);
+
+        assert_eq!(segments.len(), 3);
+        assert!(
+            matches!(segments[0], Segment::Text(text) if text.ends_with("    - handle empty lines\n"))
+        );
+        assert!(
+            matches!(segments[1], Segment::Diff(diff) if diff.starts_with("diff --git") && diff.ends_with("+new\n"))
+        );
+        assert_eq!(segments[2], Segment::Text("\ncommit 89abcde\n"));
+
+        let mut out = Vec::new();
+        write_output(&mut out, &App::new(false), show).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.starts_with("commit 1234567\nAuthor: A U Thor"));
+        assert!(output.contains("M src/lib.rs"));
+        assert!(output.ends_with("\ncommit 89abcde\n"));
+    }
+}
@@ -0,0 +1,234 @@
+use std::ops::Range;
+
+use crate::diff_file::{DiffFile, LineType};
+
+/// Returns the range of `file.lines` covered by each hunk, from its `@@` header to the line
+/// before the next one. Combined diff hunks (`@@@`) are left out, since no patch can hold them.
+pub fn hunk_ranges(file: &DiffFile) -> Vec<Range<usize>> {
+    let starts: Vec<usize> = file
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.line_type == LineType::Header && line.content.starts_with("@@ "))
+        .map(|(index, _)| index)
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| start..starts.get(i + 1).copied().unwrap_or(file.lines.len()))
+        .collect()
+}
+
+/// Returns the hunk containing line `index`, or the first hunk when `index` comes before it.
+pub fn hunk_at(file: &DiffFile, index: usize) -> Option<Range<usize>> {
+    let ranges = hunk_ranges(file);
+    ranges
+        .iter()
+        .rev()
+        .find(|range| range.start <= index)
+        .or(ranges.first())
+        .cloned()
+}
+
+/// Builds a patch holding only the hunk that contains line `index` (or the first hunk when
+/// `index` comes before it), in the form `git apply` expects.
+pub fn hunk_patch(file: &DiffFile, index: usize) -> Option<String> {
+    let hunk = hunk_at(file, index)?;
+    Some(file_patch(file, &[hunk]))
+}
+
+/// Builds the patch for the given hunks of `file`, under a single file header.
+pub fn file_patch(file: &DiffFile, hunks: &[Range<usize>]) -> String {
+    let name = file.get_name();
+    let old_name = file.get_old_name().unwrap_or(name);
+    let mode = file.get_mode().unwrap_or("100644");
+    let header = match file.get_status() {
+        'A' => format!("new file mode {mode}\n--- /dev/null\n+++ b/{name}"),
+        'D' => format!("deleted file mode {mode}\n--- a/{name}\n+++ /dev/null"),
+        'R' => format!("rename from {old_name}\nrename to {name}\n--- a/{old_name}\n+++ b/{name}"),
+        'C' => format!("copy from {old_name}\ncopy to {name}\n--- a/{old_name}\n+++ b/{name}"),
+        _ => format!("--- a/{old_name}\n+++ b/{name}"),
+    };
+    let mut patch = format!("diff --git a/{old_name} b/{name}\n{header}\n");
+
+    for line in hunks.iter().flat_map(|hunk| &file.lines[hunk.clone()]) {
+        let marker = match line.line_type {
+            LineType::Header => {
+                patch.push_str(&line.content);
+                patch.push('\n');
+                continue;
+            }
+            LineType::Added => '+',
+            LineType::Removed => '-',
+            LineType::Context => ' ',
+        };
+        patch.push(marker);
+        patch.push_str(&line.content);
+        patch.push('\n');
+        if line.no_newline {
+            patch.push_str("\\ No newline at end of file\n");
+        }
+    }
+
+    patch
+}
+
+/// Wraps `patch` in the mail header `git format-patch` writes, so that `git am` can apply it
+/// as a commit by `author` (as in `git var GIT_AUTHOR_IDENT`) with the given message.
+pub fn format_patch(patch: &str, author: &str, message: &str) -> String {
+    let (subject, body) = message
+        .trim()
+        .split_once('\n')
+        .unwrap_or((message.trim(), ""));
+    let body = body.trim();
+
+    let mut text = format!(
+        "From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001\n\
+         From: {author}\n\
+         Subject: [PATCH] {subject}\n\n"
+    );
+    if !body.is_empty() {
+        text.push_str(body);
+        text.push_str("\n\n");
+    }
+    text.push_str("---\n");
+    text.push_str(patch);
+    text
+}
+
+/// Names a patch file after its subject, as `git format-patch` does.
+pub fn patch_file_name(subject: &str) -> String {
+    let mut slug = String::new();
+    for c in subject.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_matches(['-', '.']).chars().take(52).collect();
+
+    format!("0001-{}.patch", slug.trim_end_matches(['-', '.']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_parser::parse_diff;
+
+    #[test]
+    fn test_hunk_patch() {
+        let files = parse_diff(
+            "\
+diff --git src/lib.rs src/lib.rs
+--- src/lib.rs
++++ src/lib.rs
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+@@ -10 +10,2 @@ fn main
+ x
++y
+",
+        );
+
+        assert_eq!(hunk_ranges(&files[0]), vec![0..4, 4..7]);
+        assert_eq!(
+            hunk_patch(&files[0], 7).unwrap(),
+            "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10 +10,2 @@ fn main
+ x
++y
+"
+        );
+        assert!(hunk_patch(&files[0], 0).unwrap().contains("+c\n"));
+    }
+
+    #[test]
+    fn test_combined_hunks_are_left_out() {
+        let files = parse_diff(
+            "\
+diff --cc src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@@ -1,1 -1,1 +1,1 @@@
+- a
+ -b
+++c
+",
+        );
+
+        assert!(hunk_ranges(&files[0]).is_empty());
+        assert_eq!(hunk_patch(&files[0], 0), None);
+    }
+
+    #[test]
+    fn test_hunk_patch_without_newline() {
+        let patch = "\
+diff --git a/notes.txt b/notes.txt
+--- a/notes.txt
++++ b/notes.txt
+@@ -1 +1 @@
+-old
+\\ No newline at end of file
++new
+\\ No newline at end of file
+";
+        let files = parse_diff(patch);
+
+        assert!(files[0].lines[1].no_newline && files[0].lines[2].no_newline);
+        assert_eq!(hunk_patch(&files[0], 0).unwrap(), patch);
+    }
+
+    #[test]
+    fn test_file_patch_headers() {
+        let files = parse_diff(
+            "\
+diff --git a/old.rs b/new.rs
+similarity index 90%
+rename from old.rs
+rename to new.rs
+--- a/old.rs
++++ b/new.rs
+@@ -1 +1 @@
+-a
++b
+diff --git a/run.sh b/run.sh
+new file mode 100755
+--- /dev/null
++++ b/run.sh
+@@ -0,0 +1 @@
++echo hi
+",
+        );
+
+        assert!(hunk_patch(&files[0], 0).unwrap().starts_with(
+            "diff --git a/old.rs b/new.rs\nrename from old.rs\nrename to new.rs\n--- a/old.rs\n"
+        ));
+        assert!(
+            hunk_patch(&files[1], 0)
+                .unwrap()
+                .contains("\nnew file mode 100755\n")
+        );
+    }
+
+    #[test]
+    fn test_format_patch() {
+        let text = format_patch(
+            "diff --git a/x b/x\n",
+            "A U Thor <author@example.com>",
+            "Fix the frobnicator\n\nIt was broken.\n",
+        );
+
+        assert!(text.contains("\nSubject: [PATCH] Fix the frobnicator\n\nIt was broken.\n\n---\n"));
+        assert_eq!(
+            patch_file_name("Fix the frobnicator: it's broken!"),
+            "0001-Fix-the-frobnicator-it-s-broken.patch"
+        );
+    }
+}
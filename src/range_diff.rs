@@ -0,0 +1,114 @@
+use crate::diff_file::{DiffFile, DiffLine};
+
+/// Parses `git range-diff` output into one `DiffFile` per commit pairing.
+///
+/// Each pairing is named after its position in the old and new range and the commit subject.
+/// Its status is `=` for an unchanged commit, `M` for a modified one, `D` for a commit only in
+/// the old range and `A` for one only in the new range. The lines of a modified pairing are
+/// the diff of the two commits' diffs: the outer marker gives the line type, and the content
+/// keeps the inner marker.
+pub fn parse_range_diff(text: &str) -> Vec<DiffFile> {
+    let mut files = Vec::new();
+
+    for line in text.lines() {
+        if let Some(pair) = parse_pair_line(line) {
+            files.push(pair);
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.is_empty() {
+            continue;
+        }
+        let inner = line.strip_prefix("    ").unwrap_or(line);
+        let diff_line = match inner.chars().next() {
+            Some('+') => DiffLine::new_added(inner, 0),
+            Some('-') => DiffLine::new_removed(inner, 0),
+            Some('@') => DiffLine::new_header(inner),
+            _ => DiffLine::new_context(if inner.is_empty() { " " } else { inner }, 0, 0),
+        };
+        // The nested diff has no line numbers of its own
+        file.add_line(DiffLine {
+            old_line_num: None,
+            new_line_num: None,
+            ..diff_line
+        });
+    }
+
+    files
+}
+
+/// Parses a pairing line such as `2:  1a76c00 ! 2:  89e92b9 Subject`.
+fn parse_pair_line(line: &str) -> Option<DiffFile> {
+    let mut rest = line;
+    let old_index = next_token(&mut rest)?.strip_suffix(':')?;
+    let old_hash = next_token(&mut rest)?;
+    let marker = next_token(&mut rest)?;
+    let new_index = next_token(&mut rest)?.strip_suffix(':')?;
+    let new_hash = next_token(&mut rest)?;
+
+    let status = match marker {
+        "=" => '=',
+        "!" => 'M',
+        "<" => 'D',
+        ">" => 'A',
+        _ => return None,
+    };
+    if !is_index(old_index) || !is_index(new_index) {
+        return None;
+    }
+
+    let mut file = DiffFile::new(&format!("{old_index}→{new_index} {}", rest.trim()));
+    file.set_status(status);
+    file.add_line(DiffLine::new_header(&format!("{old_hash} → {new_hash}")));
+
+    Some(file)
+}
+
+fn next_token<'a>(rest: &mut &'a str) -> Option<&'a str> {
+    let trimmed = rest.trim_start();
+    let end = trimmed.find(' ').unwrap_or(trimmed.len());
+    let (token, remainder) = trimmed.split_at(end);
+    *rest = remainder;
+    (!token.is_empty()).then_some(token)
+}
+
+fn is_index(index: &str) -> bool {
+    index == "-" || index.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_file::LineType;
+
+    #[test]
+    fn test_parse_range_diff() {
+        let text = "\
+1:  c0047eb = 1:  2c37282 two
+2:  1a76c00 ! 2:  89e92b9 three: the sequel
+    @@ f
+     @@
+      a
+    -+c
+    ++d
+
+-:  ------- > 3:  9a4e611 four
+";
+        let files = parse_range_diff(text);
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].get_status(), '=');
+        assert_eq!(files[1].get_name(), "2→2 three: the sequel");
+        assert_eq!(files[1].get_status(), 'M');
+        assert_eq!(files[1].lines[0].content, "1a76c00 → 89e92b9");
+        assert_eq!(files[1].lines[1].line_type, LineType::Header);
+        assert_eq!(files[1].lines[4].line_type, LineType::Removed);
+        assert_eq!(files[1].lines[4].content, "+c");
+        assert_eq!(files[1].lines[5].content, "+d");
+        assert_eq!(files[2].get_name(), "-→3 four");
+        assert_eq!(files[2].get_status(), 'A');
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{Context, Result};
+
+use crate::{commit_log::Commit, diff_source::DiffSource, git};
+
+/// A pull-request style comparison: the changes on `head` since it branched from `base`.
+pub struct Review {
+    pub base: String,
+    pub head: String,
+    /// The common ancestor the diff starts from.
+    pub merge_base: Commit,
+    paths: Vec<String>,
+}
+
+impl Review {
+    /// Finds the merge base of `base` and `head` (or `HEAD`).
+    pub fn new(base: &str, head: Option<&str>, paths: Vec<String>) -> Result<Self> {
+        git::ensure_repository()?;
+
+        let head = head.unwrap_or("HEAD");
+        let merge_base = git::run(&["merge-base", base, head])
+            .with_context(|| format!("no merge base between {base} and {head}"))?;
+
+        Ok(Self {
+            base: base.to_string(),
+            head: head.to_string(),
+            merge_base: Commit::load(merge_base.trim())?,
+            paths,
+        })
+    }
+
+    /// Returns the source for the diff from the merge base to the head.
+    pub fn source(&self) -> DiffSource {
+        let revisions = [self.merge_base.hash.clone(), self.head.clone()];
+
+        #[cfg(feature = "native-git")]
+        if let Some(native) =
+            crate::git_native::NativeDiff::from_args(false, None, &revisions, &self.paths)
+        {
+            return DiffSource::Native(native);
+        }
+
+        let mut args = revisions.to_vec();
+        args.push("--".to_string());
+        args.extend(self.paths.iter().cloned());
+        DiffSource::Git(args)
+    }
+}
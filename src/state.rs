@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::{diff_source::DiffSource, git};
+
+/// Returns the file under `.git/diffscape/` that stores `kind` state for the range compared
+/// by `source`, or `None` when the source is not a repository range.
+pub fn state_file(source: &DiffSource, kind: &str) -> Result<Option<PathBuf>> {
+    let Some(range) = source.range_key() else {
+        return Ok(None);
+    };
+
+    let git_dir = git::run(&["rev-parse", "--git-common-dir"])?;
+    Ok(Some(
+        PathBuf::from(git_dir.trim())
+            .join("diffscape")
+            .join(format!("{kind}-{:016x}", fnv1a(range.as_bytes()))),
+    ))
+}
+
+/// A 64-bit FNV-1a hash, which unlike `DefaultHasher` is stable across Rust releases.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -0,0 +1,87 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{diff_file::DiffFile, diff_source::DiffSource, git, patch::hunk_patch};
+
+/// The `status` mode, which lists the staged changes followed by the unstaged ones, like
+/// `git status`.
+#[derive(Clone)]
+pub struct Status {
+    paths: Vec<String>,
+    /// How many of the app's files, from the top of the list, are staged.
+    pub staged_count: usize,
+}
+
+impl Status {
+    pub fn new(paths: Vec<String>) -> Self {
+        Self {
+            paths,
+            staged_count: 0,
+        }
+    }
+
+    /// The changes in the index, like `git diff --cached`.
+    pub fn staged_source(&self) -> DiffSource {
+        DiffSource::Git(self.git_args(&["--cached"]))
+    }
+
+    /// The changes in the working tree, like `git diff`.
+    pub fn unstaged_source(&self) -> DiffSource {
+        DiffSource::Git(self.git_args(&[]))
+    }
+
+    fn git_args(&self, options: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = options.iter().map(|option| option.to_string()).collect();
+        if !self.paths.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.paths.iter().cloned());
+        }
+        args
+    }
+
+    pub fn is_staged(&self, index: usize) -> bool {
+        index < self.staged_count
+    }
+}
+
+/// Stages the hunk containing line `index` of an unstaged `file`, or unstages it from a
+/// staged one.
+pub fn toggle_hunk(file: &DiffFile, index: usize, staged: bool) -> Result<()> {
+    let Some(patch) = hunk_patch(file, index) else {
+        bail!(
+            "no hunk to {} in {}",
+            if staged { "unstage" } else { "stage" },
+            file.get_name()
+        );
+    };
+
+    let root = git::run(&["rev-parse", "--show-toplevel"])?;
+    let mut command = Command::new("git");
+    command.args(["-C", root.trim(), "apply", "--cached"]);
+    if staged {
+        command.arg("--reverse");
+    }
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run git apply")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "`git apply` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use tempfile::NamedTempFile;
+
+/// Writes `text` to a new `diffscape-<kind>-*.txt` file in the temp directory. The file is
+/// deleted when dropped unless it is kept.
+///
+/// It is created exclusively under a fresh random name, so nobody else can plant a symlink in
+/// its place or overwrite it.
+pub fn write_temp_file(kind: &str, text: &str) -> Result<NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("diffscape-{kind}-"))
+        .suffix(".txt")
+        .tempfile()
+        .context("failed to create a temporary file")?;
+    file.write_all(text.as_bytes())
+        .and_then(|()| file.flush())
+        .with_context(|| format!("failed to write {}", file.path().display()))?;
+
+    Ok(file)
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+
+use crate::{app::App, diff_file::DiffFile, ui::unified_diff::build_unified_lines};
+
+/// Writes every file of the app as an ANSI-coloured unified diff.
+///
+/// Lines are built by the same code as the unified view, so the output matches the TUI.
+pub fn write_ansi_diff<W: Write>(out: &mut W, app: &App) -> io::Result<()> {
+    write_ansi_files(out, &app.files, app)
+}
+
+/// Writes `files` as ANSI-coloured unified diffs, separated by blank lines.
+pub fn write_ansi_files<W: Write>(out: &mut W, files: &[DiffFile], app: &App) -> io::Result<()> {
+    for (index, file) in files.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        write_file_header(out, file)?;
+
+        for line in build_unified_lines(file, 0, file.line_count(), app) {
+            write_line(out, &line)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_file_header<W: Write>(out: &mut W, file: &DiffFile) -> io::Result<()> {
+    let status_color = match file.get_status() {
+        'A' => Color::Green,
+        'D' => Color::Red,
+        'M' => Color::Yellow,
+        _ => Color::White,
+    };
+    let title = format!("{} {}", file.get_status(), file.get_name());
+    let rule = "─".repeat(title.chars().count() + 2);
+
+    let style = Style::default()
+        .fg(status_color)
+        .add_modifier(Modifier::BOLD);
+    writeln!(out, "{}{}\x1b[0m", sgr(style), rule)?;
+    writeln!(out, "{} {}\x1b[0m", sgr(style), title)?;
+    writeln!(out, "{}{}\x1b[0m", sgr(style), rule)
+}
+
+fn write_line<W: Write>(out: &mut W, line: &Line) -> io::Result<()> {
+    for span in &line.spans {
+        write!(out, "{}{}\x1b[0m", sgr(span.style), span.content)?;
+    }
+    writeln!(out)
+}
+
+/// Converts a ratatui style into an SGR escape sequence.
+fn sgr(style: Style) -> String {
+    let mut codes: Vec<String> = Vec::new();
+
+    if style.add_modifier.contains(Modifier::BOLD) {
+        codes.push("1".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        codes.push("3".to_string());
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        codes.push("4".to_string());
+    }
+    if let Some(fg) = style.fg.and_then(|color| color_code(color, 30)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|color| color_code(color, 40)) {
+        codes.push(bg);
+    }
+
+    if codes.is_empty() {
+        String::new()
+    } else {
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// Returns the SGR parameters for a colour, where `base` is 30 for foreground and 40 for
+/// background.
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => format!("{}", base),
+        Color::Red => format!("{}", base + 1),
+        Color::Green => format!("{}", base + 2),
+        Color::Yellow => format!("{}", base + 3),
+        Color::Blue => format!("{}", base + 4),
+        Color::Magenta => format!("{}", base + 5),
+        Color::Cyan => format!("{}", base + 6),
+        Color::Gray => format!("{}", base + 7),
+        Color::DarkGray => format!("{}", base + 60),
+        Color::LightRed => format!("{}", base + 61),
+        Color::LightGreen => format!("{}", base + 62),
+        Color::LightYellow => format!("{}", base + 63),
+        Color::LightBlue => format!("{}", base + 64),
+        Color::LightMagenta => format!("{}", base + 65),
+        Color::LightCyan => format!("{}", base + 66),
+        Color::White => format!("{}", base + 67),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        Color::Indexed(index) => format!("{};5;{}", base + 8, index),
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sgr() {
+        assert_eq!(sgr(Style::default()), "");
+        assert_eq!(
+            sgr(Style::default()
+                .fg(Color::Rgb(1, 2, 3))
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD)),
+            "\x1b[1;38;2;1;2;3;44m"
+        );
+        assert_eq!(sgr(Style::default().fg(Color::DarkGray)), "\x1b[90m");
+    }
+}
@@ -0,0 +1,30 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::{
+    app::App,
+    diff_file::{DiffFile, DiffLine},
+};
+
+/// Builds the lines showing the review comments on `line`, indented past the line numbers.
+pub fn comment_lines<'a>(app: &'a App, file: &DiffFile, line: &DiffLine) -> Vec<Line<'a>> {
+    app.comments
+        .comments
+        .iter()
+        .filter(|comment| comment.is_on(file.get_name(), line))
+        .map(|comment| {
+            Line::from(vec![
+                Span::raw("          "),
+                Span::styled("▌ ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    comment.body.as_str(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::ITALIC),
+                ),
+            ])
+        })
+        .collect()
+}
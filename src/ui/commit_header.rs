@@ -0,0 +1,93 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::{app::App, commit_log::CommitDetails};
+
+/// Returns the height of the commit header panel, including its borders.
+pub fn commit_header_height(app: &App, available: u16) -> u16 {
+    let Some(details) = &app.commit_details else {
+        return 0;
+    };
+
+    if app.expand_commit_header {
+        let lines = header_lines(details).len() as u16 + 2;
+        lines.min(available / 2).max(3)
+    } else {
+        3
+    }
+}
+
+pub fn render_commit_header(f: &mut Frame, area: Rect, app: &App) {
+    let Some(details) = &app.commit_details else {
+        return;
+    };
+
+    let (lines, title) = if app.expand_commit_header {
+        (header_lines(details), "Commit (c: collapse)")
+    } else {
+        let short_hash: String = details.hash.chars().take(12).collect();
+        let summary = Line::from(vec![
+            Span::styled(format!("{short_hash} "), Style::default().fg(Color::Yellow)),
+            Span::raw(details.subject()),
+        ]);
+        (vec![summary], "Commit (c: expand)")
+    };
+
+    let header = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(header, area);
+}
+
+fn header_lines(details: &CommitDetails) -> Vec<Line<'_>> {
+    let label = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("commit    ", label),
+            Span::styled(details.hash.as_str(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(vec![
+            Span::styled("Author:   ", label),
+            Span::raw(format!("{}  {}", details.author, details.author_date)),
+        ]),
+        Line::from(vec![
+            Span::styled("Commit:   ", label),
+            Span::raw(format!("{}  {}", details.committer, details.committer_date)),
+        ]),
+    ];
+    if !details.parents.is_empty() {
+        let label_text = if details.parents.len() > 1 {
+            "Parents:  "
+        } else {
+            "Parent:   "
+        };
+        lines.push(Line::from(vec![
+            Span::styled(label_text, label),
+            Span::styled(
+                details.parents.join(" "),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    }
+
+    lines.push(Line::raw(""));
+    lines.extend(details.message.lines().enumerate().map(|(index, line)| {
+        // The subject line stands out from the body
+        if index == 0 {
+            Line::styled(line, Style::default().add_modifier(Modifier::BOLD))
+        } else {
+            Line::raw(line)
+        }
+    }));
+
+    lines
+}
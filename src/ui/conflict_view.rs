@@ -0,0 +1,188 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::{
+    app::App,
+    conflicts::{ConflictFile, Resolution},
+};
+
+pub fn render_conflict_view(f: &mut Frame, area: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(30), Constraint::Min(0)])
+        .split(area);
+
+    render_conflict_files(f, chunks[0], app);
+
+    let Some(file) = app.conflicts.get(app.selected_conflict_file) else {
+        let paragraph = Paragraph::new(Line::styled(
+            "No merge conflicts",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Conflicts"));
+        f.render_widget(paragraph, chunks[1]);
+        return;
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(45), Constraint::Min(0)])
+        .split(chunks[1]);
+    let sides = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .split(rows[0]);
+
+    if let Some(region) = app.current_region() {
+        render_side(
+            f,
+            sides[0],
+            format!("Ours: {}", region.ours_label),
+            Some(&region.ours),
+            Color::Green,
+        );
+        render_side(
+            f,
+            sides[1],
+            "Base".to_string(),
+            region.base.as_ref(),
+            Color::Gray,
+        );
+        render_side(
+            f,
+            sides[2],
+            format!("Theirs: {}", region.theirs_label),
+            Some(&region.theirs),
+            Color::Magenta,
+        );
+    }
+
+    render_result(f, rows[1], file, app.selected_region);
+}
+
+fn render_conflict_files(f: &mut Frame, area: Rect, app: &App) {
+    let files: Vec<ListItem> = app
+        .conflicts
+        .iter()
+        .map(|file| {
+            let unresolved = file.unresolved_count();
+            let status = if unresolved == 0 {
+                Span::styled("✓ ", Style::default().fg(Color::Green))
+            } else {
+                Span::styled(
+                    format!("{unresolved} "),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )
+            };
+
+            ListItem::new(Line::from(vec![status, Span::raw(file.path.as_str())]))
+        })
+        .collect();
+
+    let list = List::new(files)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Conflicted Files"),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_stateful_widget(list, area, &mut app.conflict_file_state.clone());
+}
+
+fn render_side(
+    f: &mut Frame,
+    area: Rect,
+    title: String,
+    lines: Option<&Vec<String>>,
+    color: Color,
+) {
+    let text: Vec<Line> = match lines {
+        Some(lines) if !lines.is_empty() => {
+            lines.iter().map(|line| Line::raw(line.as_str())).collect()
+        }
+        Some(_) => vec![Line::styled(
+            "(empty)",
+            Style::default().fg(Color::DarkGray),
+        )],
+        None => vec![Line::styled(
+            "(no common ancestor)",
+            Style::default().fg(Color::DarkGray),
+        )],
+    };
+
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+/// Renders the file as it would be written, scrolled to the selected conflict region.
+fn render_result(f: &mut Frame, area: Rect, file: &ConflictFile, selected_region: usize) {
+    let (lines, ranges) = file.result();
+    let selected = ranges.get(selected_region).cloned().unwrap_or_default();
+
+    let state = match file
+        .regions()
+        .nth(selected_region)
+        .map(|region| &region.resolution)
+    {
+        Some(None) => "unresolved",
+        Some(Some(Resolution::Ours)) => "ours",
+        Some(Some(Resolution::Theirs)) => "theirs",
+        Some(Some(Resolution::Both)) => "both",
+        Some(Some(Resolution::Edited(_))) => "edited",
+        None => "",
+    };
+    let title = format!(
+        "Result: {} (conflict {}/{}, {state})",
+        file.path,
+        selected_region + 1,
+        ranges.len()
+    );
+
+    let text: Vec<Line> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut style = Style::default();
+            if ranges.iter().any(|range| range.contains(&index)) {
+                style = style.fg(Color::Yellow);
+            }
+            if selected.contains(&index) {
+                style = style.bg(Color::Rgb(40, 40, 60));
+            }
+            Line::styled(line.as_str(), style)
+        })
+        .collect();
+
+    // Keep the selected region a third of the way down the pane
+    let visible = area.height.saturating_sub(2) as usize;
+    let scroll = selected.start.saturating_sub(visible / 3);
+
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .scroll((scroll as u16, 0));
+
+    f.render_widget(paragraph, area);
+}
@@ -2,6 +2,9 @@ use ratatui::{style::Color, text::Span};
 use similar::{Algorithm, ChangeTag, TextDiff};
 use std::ops::Range;
 
+/// Background of the `@@` header of a hunk selected for export.
+pub const SELECTED_HUNK_COLOR: Color = Color::Magenta;
+
 /// Computes the ranges of changes within a line.
 /// Returns a tuple of (ranges in old text, ranges in new text) that differ.
 pub fn compute_intra_line_diff(
@@ -6,60 +6,80 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-pub fn render_footer(f: &mut Frame, area: Rect) {
-    let shortcuts = vec![Line::from(vec![
-        Span::styled(
-            "q",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Quit  "),
-        Span::styled(
-            "j/k",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Files  "),
-        Span::styled(
-            "d/u",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Scroll  "),
-        Span::styled(
-            "g/G",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Top/Bottom  "),
-        Span::styled(
-            "←/→/h/l/H/L",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":H-Scroll  "),
-        Span::styled(
-            "s",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Toggle View  "),
-        Span::styled(
-            "?",
+use crate::app::{App, View};
+
+pub fn render_footer(f: &mut Frame, area: Rect, app: &App) {
+    let mut shortcuts = match app.view {
+        View::Diff if app.yank_pending => vec![
+            ("p", "Copy Path"),
+            ("h", "Copy Hunk"),
+            ("l", "Copy Lines"),
+            ("Esc", "Cancel"),
+        ],
+        View::Diff => vec![
+            ("q", "Quit"),
+            ("j/k", "Files"),
+            ("d/u", "Scroll"),
+            ("g/G", "Top/Bottom"),
+            ("←/→/h/l/H/L", "H-Scroll"),
+            ("s", "Toggle View"),
+            ("v/n", "Viewed/Next Unviewed"),
+            ("C/X", "Comment/Export"),
+            ("e", "Edit"),
+            ("y/V", "Copy/Mark Line"),
+            ("Space/P/F", "Select Hunk/Export"),
+            ("r", "Reload"),
+        ],
+        View::Log => vec![
+            ("q", "Quit"),
+            ("j/k", "Commits"),
+            ("d/u", "Page"),
+            ("g/G", "First/Last"),
+            ("Enter", "Show Diff"),
+        ],
+        View::Conflicts => vec![
+            ("q", "Quit"),
+            ("j/k", "Files"),
+            ("n/p", "Conflicts"),
+            ("o/t/b", "Ours/Theirs/Both"),
+            ("e", "Edit"),
+            ("x", "Reset"),
+            ("w", "Write & Stage"),
+        ],
+    };
+    if app.view == View::Diff && app.commit_steps.is_some() {
+        shortcuts.push(("[/]", "Prev/Next Commit"));
+        shortcuts.push(("a", "Range Diff"));
+    }
+    if app.view == View::Diff && app.status.is_some() {
+        shortcuts.push(("S", "Stage/Unstage Hunk"));
+    }
+    if app.view == View::Diff && !app.untracked.is_empty() {
+        shortcuts.push(("U", "Untracked"));
+    }
+    if app.view == View::Diff && app.commit_details.is_some() {
+        shortcuts.push(("c", "Commit Info"));
+    }
+    if app.view == View::Diff && app.commit_log.is_some() {
+        shortcuts.push(("Esc", "Log"));
+    }
+    shortcuts.push(("?", "Hide Help"));
+
+    let mut spans = Vec::new();
+    for (index, (key, label)) in shortcuts.into_iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            key,
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(":Hide Help"),
-    ])];
+        ));
+        spans.push(Span::raw(format!(":{label}")));
+    }
 
-    let footer = Paragraph::new(shortcuts)
+    let footer = Paragraph::new(Line::from(spans))
         .block(Block::default().borders(Borders::ALL).title("Shortcuts"))
         .style(Style::default().fg(Color::White))
         .wrap(Wrap { trim: false });
@@ -0,0 +1,221 @@
+use std::io::{self, Write};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+
+use crate::{
+    app::App,
+    diff_file::DiffFile,
+    ui::{side_by_side_diff::build_side_by_side_lines, unified_diff::build_unified_lines},
+};
+
+const STYLESHEET: &str = "
+body { margin: 0; display: flex; background: #2b303b; color: #c0c5ce; font-family: sans-serif; }
+nav { position: sticky; top: 0; height: 100vh; overflow-y: auto; min-width: 16em; max-width: 24em;
+      padding: 1em; box-sizing: border-box; background: #232730; }
+nav a { display: block; color: inherit; text-decoration: none; white-space: nowrap; overflow: hidden;
+        text-overflow: ellipsis; padding: 2px 0; font-family: monospace; }
+nav a:hover { text-decoration: underline; }
+main { flex: 1; min-width: 0; padding: 1em; }
+button { margin-bottom: 1em; }
+section { margin-bottom: 2em; }
+h2 { font-family: monospace; font-size: 1em; padding: 0.4em; background: #343d46; margin: 0 0 0.5em; }
+pre { margin: 0; overflow-x: auto; font-size: 13px; }
+.line { white-space: pre; min-height: 1.35em; line-height: 1.35em; }
+.split { display: grid; grid-template-columns: 1fr 1fr; gap: 0.5em; }
+.split pre { min-width: 0; }
+body.split-view .unified, body:not(.split-view) .split { display: none; }
+.status-A { color: #00c000; } .status-D { color: #e00000; } .status-M { color: #d0c000; }
+";
+
+const SCRIPT: &str = "
+function toggleView() {
+  const split = document.body.classList.toggle('split-view');
+  document.getElementById('toggle').textContent = split ? 'Unified view' : 'Split view';
+}
+";
+
+/// Writes every file of the app as a self-contained HTML page, with a file list, syntax
+/// highlighting and a button switching between unified and split views.
+///
+/// Lines are built by the same code as the TUI views, so the page matches them.
+pub fn write_html<W: Write>(out: &mut W, app: &App, title: &str) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>diffscape: {}</title>", escape(title))?;
+    writeln!(
+        out,
+        "<style>{STYLESHEET}</style>\n<script>{SCRIPT}</script>"
+    )?;
+    writeln!(out, "</head>\n<body>\n<nav>")?;
+    for (index, file) in app.files.iter().enumerate() {
+        writeln!(
+            out,
+            "<a href=\"#file-{index}\"><span class=\"status-{status}\">{status}</span> {}</a>",
+            escape(file.get_name()),
+            status = file.get_status(),
+        )?;
+    }
+    writeln!(out, "</nav>\n<main>")?;
+    writeln!(
+        out,
+        "<button id=\"toggle\" onclick=\"toggleView()\">Split view</button>"
+    )?;
+
+    for (index, file) in app.files.iter().enumerate() {
+        write_file(out, app, file, index)?;
+    }
+
+    writeln!(out, "</main>\n</body>\n</html>")
+}
+
+fn write_file<W: Write>(out: &mut W, app: &App, file: &DiffFile, index: usize) -> io::Result<()> {
+    writeln!(out, "<section id=\"file-{index}\">")?;
+    writeln!(
+        out,
+        "<h2><span class=\"status-{status}\">{status}</span> {}</h2>",
+        escape(file.get_name()),
+        status = file.get_status(),
+    )?;
+
+    write!(out, "<pre class=\"unified\">")?;
+    for line in build_unified_lines(file, 0, file.line_count(), app) {
+        write_line(out, &line)?;
+    }
+    writeln!(out, "</pre>")?;
+
+    // The side without a counterpart gets empty lines, which keep the two panes aligned
+    let (old_lines, new_lines) = build_side_by_side_lines(file, 0, file.line_count(), 0, app);
+    write!(out, "<div class=\"split\"><pre>")?;
+    for line in &old_lines {
+        write_line(out, line)?;
+    }
+    write!(out, "</pre><pre>")?;
+    for line in &new_lines {
+        write_line(out, line)?;
+    }
+    writeln!(out, "</pre></div>\n</section>")
+}
+
+fn write_line<W: Write>(out: &mut W, line: &Line) -> io::Result<()> {
+    write!(out, "<div class=\"line\">")?;
+    for span in &line.spans {
+        match css(span.style) {
+            css if css.is_empty() => write!(out, "{}", escape(&span.content))?,
+            css => write!(
+                out,
+                "<span style=\"{css}\">{}</span>",
+                escape(&span.content)
+            )?,
+        }
+    }
+    write!(out, "</div>")
+}
+
+/// Converts a ratatui style into inline CSS.
+fn css(style: Style) -> String {
+    let mut properties = Vec::new();
+
+    if let Some(fg) = style.fg.and_then(color_hex) {
+        properties.push(format!("color:{fg}"));
+    }
+    if let Some(bg) = style.bg.and_then(color_hex) {
+        properties.push(format!("background:{bg}"));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        properties.push("font-weight:bold".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        properties.push("font-style:italic".to_string());
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        properties.push("text-decoration:underline".to_string());
+    }
+
+    properties.join(";")
+}
+
+/// Returns the CSS colour for a terminal colour, using the xterm palette for named colours.
+fn color_hex(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Rgb(r, g, b) => (r, g, b),
+        // The 256-colour palette isn't used by the views
+        Color::Indexed(_) => return None,
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_parser::parse_diff;
+
+    #[test]
+    fn test_write_html() {
+        let mut app = App::new(false);
+        app.files = parse_diff(
+            "diff --git a/a<b>.txt b/a<b>.txt\n\
+             --- a/a<b>.txt\n\
+             +++ b/a<b>.txt\n\
+             @@ -1 +1 @@\n\
+             -old\n\
+             +<tag> & \"x\"\n",
+        );
+
+        let mut out = Vec::new();
+        write_html(&mut out, &app, "main..<feature>").unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains("<title>diffscape: main..&lt;feature&gt;</title>"));
+        assert!(
+            html.contains(
+                "<a href=\"#file-0\"><span class=\"status-M\">M</span> a&lt;b&gt;.txt</a>"
+            )
+        );
+        assert!(html.contains("<section id=\"file-0\">"));
+        assert!(html.contains("&lt;tag&gt; &amp; &quot;x&quot;"));
+        assert!(!html.contains("<tag>"));
+    }
+
+    #[test]
+    fn test_css() {
+        assert_eq!(css(Style::default()), "");
+        assert_eq!(
+            css(Style::default()
+                .fg(Color::Rgb(1, 2, 3))
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD)),
+            "color:#010203;background:#0000ee;font-weight:bold"
+        );
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use crate::app::App;
+
+pub fn render_log_view(f: &mut Frame, area: Rect, app: &App) {
+    let Some(log) = &app.commit_log else {
+        return;
+    };
+
+    let title = if log.is_exhausted() {
+        format!("Commits ({})", log.commits.len())
+    } else {
+        format!("Commits ({}+)", log.commits.len())
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if log.commits.is_empty() {
+        let paragraph = Paragraph::new(Line::styled(
+            "No commits",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .alignment(Alignment::Center)
+        .block(block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let author_width = log
+        .commits
+        .iter()
+        .map(|commit| commit.author.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(20);
+
+    let commits: Vec<ListItem> = log
+        .commits
+        .iter()
+        .map(|commit| {
+            let author: String = commit.author.chars().take(author_width).collect();
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", commit.short_hash),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{} ", commit.date),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!("{author:author_width$} "),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::raw(commit.subject.as_str()),
+            ]))
+        })
+        .collect();
+
+    let commit_list = List::new(commits).block(block).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    f.render_stateful_widget(commit_list, area, &mut app.commit_list_state.clone());
+}
@@ -1,6 +1,12 @@
+pub mod ansi;
+mod comments;
+mod commit_header;
+mod conflict_view;
 pub mod diff_utils;
 mod footer;
 mod highlight_line;
+pub mod html;
+mod log_view;
 pub mod render_ui;
 mod side_by_side_diff;
 mod unified_diff;
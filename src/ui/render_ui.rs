@@ -1,15 +1,19 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
 use crate::{
-    app::App,
+    app::{App, View},
     ui::{
-        footer::render_footer, side_by_side_diff::render_side_by_side_diff,
+        commit_header::{commit_header_height, render_commit_header},
+        conflict_view::render_conflict_view,
+        footer::render_footer,
+        log_view::render_log_view,
+        side_by_side_diff::render_side_by_side_diff,
         unified_diff::render_unified_diff,
     },
 };
@@ -17,16 +21,59 @@ use crate::{
 pub fn ui(f: &mut Frame, app: &App) {
     let size = f.area();
 
-    // Main layout with optional footer
-    let (content_area, footer_area) = if app.show_shortcuts {
-        let main_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(3)])
-            .split(size);
-        (main_chunks[0], Some(main_chunks[1]))
+    // Main layout with optional status line and footer
+    let status_height = if app.status_message.is_some() || app.comment_draft.is_some() {
+        1
     } else {
-        (size, None)
+        0
     };
+    let footer_height = if app.show_shortcuts { 3 } else { 0 };
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(status_height),
+            Constraint::Length(footer_height),
+        ])
+        .split(size);
+    let content_area = main_chunks[0];
+
+    if let Some(draft) = &app.comment_draft {
+        render_comment_input(f, main_chunks[1], app, draft);
+    } else if let Some(message) = &app.status_message {
+        render_status_line(f, main_chunks[1], message);
+    }
+
+    // Footer with keyboard shortcuts (if enabled)
+    if app.show_shortcuts {
+        render_footer(f, main_chunks[2], app);
+    }
+
+    match app.view {
+        View::Log => return render_log_view(f, content_area, app),
+        View::Conflicts => return render_conflict_view(f, content_area, app),
+        View::Diff => {}
+    }
+
+    // Breadcrumb naming what is compared, then commit metadata (if viewing a commit)
+    let breadcrumb = breadcrumb(app);
+    let breadcrumb_height = if breadcrumb.is_some() { 1 } else { 0 };
+    let header_height = commit_header_height(app, content_area.height);
+    let content_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(breadcrumb_height),
+            Constraint::Length(header_height),
+            Constraint::Min(0),
+        ])
+        .split(content_area);
+    if let Some((range, position)) = breadcrumb {
+        render_breadcrumb(f, content_chunks[0], range, position);
+    }
+    if header_height > 0 {
+        render_commit_header(f, content_chunks[1], app);
+    }
+    let content_area = content_chunks[2];
 
     // Content layout (file list and diff)
     let chunks = Layout::default()
@@ -34,15 +81,33 @@ pub fn ui(f: &mut Frame, app: &App) {
         .constraints([Constraint::Length(30), Constraint::Min(0)])
         .split(content_area);
 
-    // File list
-    let files: Vec<ListItem> = app
+    render_file_list(f, chunks[0], app);
+
+    // Diff content
+    if let Some(file) = app.files.get(app.selected_file) {
+        let diff_area = chunks[1];
+
+        if app.show_side_by_side && diff_area.width >= 120 {
+            render_side_by_side_diff(f, diff_area, file, app.scroll_offset, app);
+        } else {
+            render_unified_diff(f, diff_area, file, app.scroll_offset, app);
+        }
+    } else if app.files.is_empty() {
+        render_no_changes(f, chunks[1]);
+    }
+}
+
+fn render_file_list(f: &mut Frame, area: Rect, app: &App) {
+    let mut files: Vec<ListItem> = app
         .files
         .iter()
-        .map(|file| {
+        .enumerate()
+        .map(|(index, file)| {
             let status_color = match file.get_status() {
                 'A' => Color::Green,
                 'D' => Color::Red,
                 'M' => Color::Yellow,
+                '=' => Color::DarkGray,
                 _ => Color::White,
             };
 
@@ -53,11 +118,42 @@ pub fn ui(f: &mut Frame, app: &App) {
                         .fg(status_color)
                         .add_modifier(Modifier::BOLD),
                 ),
+                if app.viewed.is_viewed(index) {
+                    Span::styled("✓ ", Style::default().fg(Color::Green))
+                } else {
+                    Span::raw("  ")
+                },
                 Span::raw(file.get_name()),
             ]))
         })
         .collect();
 
+    // In status mode the staged and unstaged files get a heading each
+    let mut state = app.file_list_state.clone();
+    if let Some(status) = &app.status {
+        let section = |title: &str, count: usize| {
+            ListItem::new(Line::styled(
+                format!("{title} ({count})"),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+        let unstaged_count = app.files.len() - status.staged_count;
+        files.insert(
+            status.staged_count,
+            section("Unstaged changes", unstaged_count),
+        );
+        files.insert(0, section("Staged changes", status.staged_count));
+
+        let headings = if status.is_staged(app.selected_file) {
+            1
+        } else {
+            2
+        };
+        state.select(Some(app.selected_file + headings));
+    }
+
     let files_list = List::new(files)
         .block(Block::default().borders(Borders::ALL).title("Files"))
         .highlight_style(
@@ -66,21 +162,101 @@ pub fn ui(f: &mut Frame, app: &App) {
                 .add_modifier(Modifier::BOLD),
         );
 
-    f.render_stateful_widget(files_list, chunks[0], &mut app.file_list_state.clone());
-
-    // Diff content
-    if let Some(file) = app.files.get(app.selected_file) {
-        let diff_area = chunks[1];
+    f.render_stateful_widget(files_list, area, &mut state);
+}
 
-        if app.show_side_by_side && diff_area.width >= 120 {
-            render_side_by_side_diff(f, diff_area, file, app.scroll_offset, app);
-        } else {
-            render_unified_diff(f, diff_area, file, app.scroll_offset, app);
-        }
+/// Returns the compared range and the position within it, when there is one to show.
+fn breadcrumb(app: &App) -> Option<(String, String)> {
+    if let Some(steps) = &app.commit_steps {
+        let position = match steps.current_commit() {
+            Some(commit) => {
+                let short_hash: String = commit.chars().take(7).collect();
+                format!(
+                    "commit {}/{} ({short_hash})",
+                    steps.position,
+                    steps.commits.len()
+                )
+            }
+            None => format!("all {} commits", steps.commits.len()),
+        };
+        return Some((steps.range.clone(), position));
     }
 
-    // Footer with keyboard shortcuts (if enabled)
-    if let Some(footer_area) = footer_area {
-        render_footer(f, footer_area);
-    }
+    app.review.as_ref().map(|review| {
+        let merge_base = &review.merge_base;
+        (
+            format!("{}...{}", review.base, review.head),
+            format!(
+                "merge base {} {} ({})",
+                merge_base.short_hash, merge_base.subject, merge_base.date
+            ),
+        )
+    })
+}
+
+fn render_breadcrumb(f: &mut Frame, area: Rect, range: String, position: String) {
+    let breadcrumb = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" {range} "),
+            Style::default().fg(Color::Black).bg(Color::Cyan),
+        ),
+        Span::raw(" › "),
+        Span::styled(position, Style::default().add_modifier(Modifier::BOLD)),
+    ]));
+
+    f.render_widget(breadcrumb, area);
+}
+
+fn render_status_line(f: &mut Frame, area: Rect, message: &str) {
+    let status = Paragraph::new(Line::styled(
+        message,
+        Style::default().fg(Color::Black).bg(Color::Yellow),
+    ))
+    .style(Style::default().bg(Color::Yellow));
+
+    f.render_widget(status, area);
+}
+
+fn render_comment_input(f: &mut Frame, area: Rect, app: &App, draft: &str) {
+    let anchor = match app.current_line() {
+        Some((file, line)) => match (line.new_line_num, line.old_line_num) {
+            (Some(new), _) => format!("{}:{new}", file.get_name()),
+            (None, Some(old)) => format!("{}:-{old}", file.get_name()),
+            (None, None) => file.get_name().to_string(),
+        },
+        None => String::new(),
+    };
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" Comment on {anchor} "),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ),
+        Span::raw(" "),
+        Span::raw(draft),
+        Span::styled("█", Style::default().fg(Color::Yellow)),
+    ]));
+
+    f.render_widget(input, area);
+}
+
+fn render_no_changes(f: &mut Frame, area: Rect) {
+    let top_padding = area.height.saturating_sub(4) / 2;
+    let mut lines = vec![Line::raw(""); top_padding as usize];
+    lines.push(Line::styled(
+        "No changes",
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+    ));
+    lines.push(Line::styled(
+        "There is nothing to show for this diff.",
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    let paragraph = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Diff"));
+
+    f.render_widget(paragraph, area);
 }
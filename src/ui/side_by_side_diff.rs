@@ -9,7 +9,10 @@ use ratatui::{
 use crate::{
     app::App,
     diff_file::{DiffFile, LineType},
-    ui::highlight_line::highlight_line_content,
+    ui::{
+        comments::comment_lines, diff_utils::SELECTED_HUNK_COLOR,
+        highlight_line::highlight_line_content,
+    },
 };
 
 pub fn render_side_by_side_diff(
@@ -26,14 +29,74 @@ pub fn render_side_by_side_diff(
 
     let visible_lines = (area.height - 2) as usize;
     let panel_width = (chunks[0].width.saturating_sub(2)) as usize; // Width minus borders
+    let end_line = (scroll_offset + visible_lines).min(file.line_count());
+    let (old_lines, new_lines) =
+        build_side_by_side_lines(file, scroll_offset, end_line, panel_width, app);
+
+    let old_text = Text::from(old_lines);
+    let new_text = Text::from(new_lines);
+
+    let old_title = format!("Old: {}", file.get_name());
+    let new_title = format!("New: {}", file.get_name());
+
+    let old_paragraph = Paragraph::new(old_text)
+        .block(Block::default().borders(Borders::ALL).title(old_title))
+        .scroll((0, app.horizontal_scroll_offset as u16));
+
+    let new_paragraph = Paragraph::new(new_text)
+        .block(Block::default().borders(Borders::ALL).title(new_title))
+        .scroll((0, app.horizontal_scroll_offset as u16));
+
+    f.render_widget(old_paragraph, chunks[0]);
+    f.render_widget(new_paragraph, chunks[1]);
+
+    // Render scrollbars for both panels
+    let total_lines = file.line_count();
+    if total_lines > visible_lines {
+        let mut scrollbar_state = ScrollbarState::new(total_lines).position(scroll_offset);
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+
+        // Scrollbar for old (left) panel
+        f.render_stateful_widget(
+            scrollbar.clone(),
+            chunks[0].inner(ratatui::layout::Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state.clone(),
+        );
+
+        // Scrollbar for new (right) panel
+        f.render_stateful_widget(
+            scrollbar,
+            chunks[1].inner(ratatui::layout::Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// Builds the old and new panel lines for `file.lines[start_line..end_line]`, padding the
+/// side without a counterpart to `panel_width`.
+pub fn build_side_by_side_lines<'a>(
+    file: &'a DiffFile,
+    start_line: usize,
+    end_line: usize,
+    panel_width: usize,
+    app: &'a App,
+) -> (Vec<Line<'a>>, Vec<Line<'a>>) {
     let syntax = app.get_syntax_for_file(file.get_name());
     let theme = app.get_theme("base16-ocean.dark");
 
     let mut old_lines = Vec::new();
     let mut new_lines = Vec::new();
 
-    let mut i = scroll_offset;
-    let end_line = (scroll_offset + visible_lines).min(file.line_count());
+    let mut i = start_line;
 
     while i < end_line {
         let diff_line = &file.lines[i];
@@ -62,6 +125,8 @@ pub fn render_side_by_side_diff(
                     theme,
                     Some((&new_ranges, Color::Rgb(0, 100, 0), Color::Rgb(0, 60, 0))),
                 ));
+                push_comments(app, file, diff_line, &mut old_lines, &mut new_lines);
+                push_comments(app, file, next_line, &mut old_lines, &mut new_lines);
 
                 i += 2;
                 continue;
@@ -107,10 +172,15 @@ pub fn render_side_by_side_diff(
                 ));
             }
             LineType::Header => {
+                let background = if app.is_hunk_selected(app.selected_file, diff_line) {
+                    SELECTED_HUNK_COLOR
+                } else {
+                    Color::Blue
+                };
                 let header_line = Line::from(vec![Span::styled(
                     &diff_line.content,
                     Style::default()
-                        .bg(Color::Blue)
+                        .bg(background)
                         .fg(Color::White)
                         .add_modifier(Modifier::BOLD),
                 )]);
@@ -118,122 +188,98 @@ pub fn render_side_by_side_diff(
                 new_lines.push(header_line);
             }
         }
+        push_comments(app, file, diff_line, &mut old_lines, &mut new_lines);
         i += 1;
     }
 
-    let old_text = Text::from(old_lines);
-    let new_text = Text::from(new_lines);
-
-    let old_title = format!("Old: {}", file.get_name());
-    let new_title = format!("New: {}", file.get_name());
+    (old_lines, new_lines)
+}
 
-    let old_paragraph = Paragraph::new(old_text)
-        .block(Block::default().borders(Borders::ALL).title(old_title))
-        .scroll((0, app.horizontal_scroll_offset as u16));
+/// Adds the comments on `diff_line` beneath it in the panel of its side, keeping the
+/// other panel aligned with blank lines.
+fn push_comments<'a>(
+    app: &'a App,
+    file: &DiffFile,
+    diff_line: &crate::diff_file::DiffLine,
+    old_lines: &mut Vec<Line<'a>>,
+    new_lines: &mut Vec<Line<'a>>,
+) {
+    let comments = comment_lines(app, file, diff_line);
+    let blanks = vec![Line::raw(""); comments.len()];
+    if diff_line.new_line_num.is_some() {
+        old_lines.extend(blanks);
+        new_lines.extend(comments);
+    } else {
+        old_lines.extend(comments);
+        new_lines.extend(blanks);
+    }
+}
 
-    let new_paragraph = Paragraph::new(new_text)
-        .block(Block::default().borders(Borders::ALL).title(new_title))
-        .scroll((0, app.horizontal_scroll_offset as u16));
+fn render_diff_line<'a>(
+    diff_line: &'a crate::diff_file::DiffLine,
+    syntax: Option<&syntect::parsing::SyntaxReference>,
+    syntax_set: &syntect::parsing::SyntaxSet,
+    theme: &syntect::highlighting::Theme,
+    intra_line_highlight: Option<(&[std::ops::Range<usize>], Color, Color)>,
+) -> Line<'a> {
+    let _line_num_text = match (&diff_line.old_line_num, &diff_line.new_line_num) {
+        (Some(old), Some(new)) => format!("{:4}:{:4} ", old, new),
+        (Some(old), None) => format!("{:4}:     ", old),
+        (None, Some(new)) => format!("     {:4} ", new),
+        (None, None) => "         ".to_string(),
+    };
 
-    f.render_widget(old_paragraph, chunks[0]);
-    f.render_widget(new_paragraph, chunks[1]);
+    let mut spans = vec![Span::styled(
+        _line_num_text,
+        Style::default().fg(Color::DarkGray),
+    )];
 
-    // Render scrollbars for both panels
-    let total_lines = file.line_count();
-    if total_lines > visible_lines {
-        let mut scrollbar_state = ScrollbarState::new(total_lines).position(scroll_offset);
+    let (bg_color, prefix) = match diff_line.line_type {
+        LineType::Added => (Some(Color::Rgb(0, 100, 0)), "+ "),
+        LineType::Removed => (Some(Color::Rgb(139, 0, 0)), "- "),
+        LineType::Context => (None, "  "),
+        LineType::Header => (Some(Color::Blue), "@ "),
+    };
 
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓"));
+    // Add prefix
+    spans.push(Span::styled(
+        prefix,
+        match bg_color {
+            Some(bg) => Style::default().bg(bg).fg(Color::White),
+            None => Style::default().fg(Color::White),
+        },
+    ));
 
-        // Scrollbar for old (left) panel
-        f.render_stateful_widget(
-            scrollbar.clone(),
-            chunks[0].inner(ratatui::layout::Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state.clone(),
-        );
-
-        // Scrollbar for new (right) panel
-        f.render_stateful_widget(
-            scrollbar,
-            chunks[1].inner(ratatui::layout::Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
-    }
-
-    fn render_diff_line<'a>(
-        diff_line: &'a crate::diff_file::DiffLine,
-        syntax: Option<&syntect::parsing::SyntaxReference>,
-        syntax_set: &syntect::parsing::SyntaxSet,
-        theme: &syntect::highlighting::Theme,
-        intra_line_highlight: Option<(&[std::ops::Range<usize>], Color, Color)>,
-    ) -> Line<'a> {
-        let _line_num_text = match (&diff_line.old_line_num, &diff_line.new_line_num) {
-            (Some(old), Some(new)) => format!("{:4}:{:4} ", old, new),
-            (Some(old), None) => format!("{:4}:     ", old),
-            (None, Some(new)) => format!("     {:4} ", new),
-            (None, None) => "         ".to_string(),
-        };
-
-        let mut spans = vec![Span::styled(
-            _line_num_text,
-            Style::default().fg(Color::DarkGray),
-        )];
-
-        let (bg_color, prefix) = match diff_line.line_type {
-            LineType::Added => (Some(Color::Rgb(0, 100, 0)), "+ "),
-            LineType::Removed => (Some(Color::Rgb(139, 0, 0)), "- "),
-            LineType::Context => (None, "  "),
-            LineType::Header => (Some(Color::Blue), "@ "),
-        };
-
-        // Add prefix
+    if diff_line.line_type == LineType::Header {
         spans.push(Span::styled(
-            prefix,
-            match bg_color {
-                Some(bg) => Style::default().bg(bg).fg(Color::White),
-                None => Style::default().fg(Color::White),
-            },
+            &diff_line.content,
+            Style::default()
+                .bg(Color::Blue)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
         ));
+    } else {
+        let highlighted_spans =
+            highlight_line_content(&diff_line.content, syntax, syntax_set, theme);
 
-        if diff_line.line_type == LineType::Header {
-            spans.push(Span::styled(
-                &diff_line.content,
-                Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ));
-        } else {
-            let highlighted_spans =
-                highlight_line_content(&diff_line.content, syntax, syntax_set, theme);
-
-            if let Some((ranges, base_bg, highlight_bg)) = intra_line_highlight {
-                let diff_spans = crate::ui::diff_utils::apply_diff_highlight(
-                    highlighted_spans,
-                    ranges,
-                    base_bg,
-                    highlight_bg,
-                );
-                spans.extend(diff_spans);
-            } else if let Some(bg) = bg_color {
-                for span in highlighted_spans {
-                    let mut new_style = span.style;
-                    new_style = new_style.bg(bg);
-                    spans.push(Span::styled(span.content, new_style));
-                }
-            } else {
-                spans.extend(highlighted_spans);
+        if let Some((ranges, base_bg, highlight_bg)) = intra_line_highlight {
+            let diff_spans = crate::ui::diff_utils::apply_diff_highlight(
+                highlighted_spans,
+                ranges,
+                base_bg,
+                highlight_bg,
+            );
+            spans.extend(diff_spans);
+        } else if let Some(bg) = bg_color {
+            for span in highlighted_spans {
+                let mut new_style = span.style;
+                new_style = new_style.bg(bg);
+                spans.push(Span::styled(span.content, new_style));
             }
+        } else {
+            spans.extend(highlighted_spans);
         }
-
-        Line::from(spans)
     }
+
+    Line::from(spans)
 }
@@ -9,7 +9,10 @@ use ratatui::{
 use crate::{
     app::App,
     diff_file::{DiffFile, LineType},
-    ui::highlight_line::highlight_line_content,
+    ui::{
+        comments::comment_lines, diff_utils::SELECTED_HUNK_COLOR,
+        highlight_line::highlight_line_content,
+    },
 };
 
 pub fn render_unified_diff(
@@ -20,14 +23,52 @@ pub fn render_unified_diff(
     app: &App,
 ) {
     let visible_lines = (area.height - 2) as usize; // Account for borders
-    let _end_line = (scroll_offset + visible_lines).min(file.line_count());
+    let end_line = (scroll_offset + visible_lines).min(file.line_count());
+    let lines = build_unified_lines(file, scroll_offset, end_line, app);
+
+    let diff_text = Text::from(lines);
+    let paragraph = Paragraph::new(diff_text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(file.get_name()),
+        )
+        .scroll((0, app.horizontal_scroll_offset as u16));
+
+    f.render_widget(paragraph, area);
 
+    // Render scrollbar
+    let total_lines = file.line_count();
+    if total_lines > visible_lines {
+        let mut scrollbar_state = ScrollbarState::new(total_lines).position(scroll_offset);
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+
+        f.render_stateful_widget(
+            scrollbar,
+            area.inner(ratatui::layout::Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// Builds the highlighted unified diff lines for `file.lines[start_line..end_line]`.
+pub fn build_unified_lines<'a>(
+    file: &'a DiffFile,
+    start_line: usize,
+    end_line: usize,
+    app: &'a App,
+) -> Vec<Line<'a>> {
     let syntax = app.get_syntax_for_file(file.get_name());
     let theme = app.get_theme("base16-ocean.dark");
 
     let mut lines = Vec::new();
-    let mut i = scroll_offset;
-    let end_line = (scroll_offset + visible_lines).min(file.line_count());
+    let mut i = start_line;
 
     while i < end_line {
         let diff_line = &file.lines[i];
@@ -50,6 +91,7 @@ pub fn render_unified_diff(
                     theme,
                     Some((&old_ranges, Color::Rgb(139, 0, 0), Color::Rgb(80, 0, 0))),
                 ));
+                lines.extend(comment_lines(app, file, diff_line));
 
                 // Render added line
                 lines.push(render_diff_line(
@@ -59,51 +101,27 @@ pub fn render_unified_diff(
                     theme,
                     Some((&new_ranges, Color::Rgb(0, 100, 0), Color::Rgb(0, 60, 0))),
                 ));
+                lines.extend(comment_lines(app, file, next_line));
 
                 i += 2;
                 continue;
             }
         }
 
-        lines.push(render_diff_line(
-            diff_line,
-            syntax,
-            app.get_syntax_set(),
-            theme,
-            None,
-        ));
+        let mut line = render_diff_line(diff_line, syntax, app.get_syntax_set(), theme, None);
+        if diff_line.line_type == LineType::Header
+            && app.is_hunk_selected(app.selected_file, diff_line)
+        {
+            for span in &mut line.spans {
+                span.style = span.style.bg(SELECTED_HUNK_COLOR);
+            }
+        }
+        lines.push(line);
+        lines.extend(comment_lines(app, file, diff_line));
         i += 1;
     }
 
-    let diff_text = Text::from(lines);
-    let paragraph = Paragraph::new(diff_text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(file.get_name()),
-        )
-        .scroll((0, app.horizontal_scroll_offset as u16));
-
-    f.render_widget(paragraph, area);
-
-    // Render scrollbar
-    let total_lines = file.line_count();
-    if total_lines > visible_lines {
-        let mut scrollbar_state = ScrollbarState::new(total_lines).position(scroll_offset);
-
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓"));
-
-        f.render_stateful_widget(
-            scrollbar,
-            area.inner(ratatui::layout::Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
-    }
+    lines
 }
 
 fn render_diff_line<'a>(
@@ -132,14 +150,27 @@ fn render_diff_line<'a>(
         LineType::Header => (Some(Color::Blue), "@ "),
     };
 
-    // Add prefix
-    spans.push(Span::styled(
-        prefix,
-        match bg_color {
-            Some(bg) => Style::default().bg(bg).fg(Color::White),
-            None => Style::default().fg(Color::White),
-        },
-    ));
+    let prefix_style = match bg_color {
+        Some(bg) => Style::default().bg(bg).fg(Color::White),
+        None => Style::default().fg(Color::White),
+    };
+    if diff_line.parent_markers.is_empty() || diff_line.line_type == LineType::Header {
+        spans.push(Span::styled(prefix, prefix_style));
+    } else {
+        // Combined diffs get a marker column per parent
+        for marker in diff_line.parent_markers.chars() {
+            let style = match marker {
+                '+' => prefix_style.fg(Color::LightGreen),
+                '-' => prefix_style.fg(Color::LightRed),
+                _ => prefix_style,
+            };
+            spans.push(Span::styled(
+                marker.to_string(),
+                style.add_modifier(Modifier::BOLD),
+            ));
+        }
+        spans.push(Span::styled(" ", prefix_style));
+    }
 
     if diff_line.line_type == LineType::Header {
         // Headers don't get syntax highlighting
@@ -0,0 +1,152 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{
+    diff_file::{DiffFile, DiffLine},
+    git,
+};
+
+/// Loads the untracked, non-ignored files below `paths` (or the whole repository when empty)
+/// as added files, since `git diff` leaves them out.
+pub fn load_untracked(paths: &[String]) -> Result<Vec<DiffFile>> {
+    let root = PathBuf::from(git::run(&["rev-parse", "--show-toplevel"])?.trim());
+
+    let mut args = vec![
+        "ls-files",
+        "--others",
+        "--exclude-standard",
+        "--full-name",
+        "-z",
+        "--",
+    ];
+    if paths.is_empty() {
+        // Without paths git only lists the current directory, so name the repository root
+        args.push(":/");
+    }
+    args.extend(paths.iter().map(String::as_str));
+    let output = git::run(&args)?;
+
+    Ok(output
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| untracked_entry(&root, name))
+        .collect())
+}
+
+/// Builds the added file for one untracked entry. Directories, such as nested repositories
+/// that git lists as `dir/`, are skipped, and an entry that can't be read shows the error
+/// instead of failing the whole diff.
+fn untracked_entry(root: &Path, name: &str) -> Option<DiffFile> {
+    let path = root.join(name);
+    let entry = fs::symlink_metadata(&path).and_then(|metadata| {
+        if metadata.file_type().is_symlink() {
+            // Like git, show a symlink as the text of its target
+            let target = fs::read_link(&path)?;
+            Ok(Some((
+                target.to_string_lossy().into_owned().into_bytes(),
+                "120000",
+            )))
+        } else if metadata.is_file() {
+            let mode = if is_executable(&metadata) {
+                "100755"
+            } else {
+                "100644"
+            };
+            Ok(Some((fs::read(&path)?, mode)))
+        } else {
+            Ok(None)
+        }
+    });
+
+    match entry {
+        Ok(Some((bytes, mode))) => {
+            let mut file = untracked_file(name, &bytes);
+            file.set_mode(mode);
+            Some(file)
+        }
+        Ok(None) => None,
+        Err(err) => {
+            let mut file = DiffFile::new(name);
+            file.set_status('A');
+            file.add_line(DiffLine::new_header(&format!(
+                "failed to read {name}: {err}"
+            )));
+            Some(file)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Builds an added file holding all of `bytes`, like `git diff --no-index /dev/null <name>`.
+fn untracked_file(name: &str, bytes: &[u8]) -> DiffFile {
+    let mut file = DiffFile::new(name);
+    file.set_status('A');
+
+    if bytes.contains(&0) {
+        file.add_line(DiffLine::new_header(&format!(
+            "Binary files /dev/null and {name} differ"
+        )));
+        return file;
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let count = text.lines().count();
+    if count > 0 {
+        file.add_line(DiffLine::new_header(&format!("@@ -0,0 +1,{count} @@")));
+    }
+    for (index, line) in text.lines().enumerate() {
+        file.add_line(DiffLine::new_added(&format!("+{line}"), index as u32 + 1));
+    }
+    if !text.ends_with('\n')
+        && let Some(last) = file.lines.last_mut()
+    {
+        last.no_newline = true;
+    }
+
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_file::LineType;
+
+    #[test]
+    fn test_untracked_file() {
+        let file = untracked_file("notes.txt", b"one\ntwo\n");
+
+        assert_eq!(file.get_status(), 'A');
+        assert_eq!(file.lines[0].content, "@@ -0,0 +1,2 @@");
+        assert_eq!(file.lines[2].line_type, LineType::Added);
+        assert_eq!(file.lines[2].new_line_num, Some(2));
+        assert_eq!(file.lines[2].content, "two");
+
+        let binary = untracked_file("image.png", b"\x89PNG\0");
+        assert_eq!(binary.line_count(), 1);
+
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("nested")).unwrap();
+        assert!(untracked_entry(root.path(), "nested/").is_none());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("missing", root.path().join("dangling")).unwrap();
+            let link = untracked_entry(root.path(), "dangling").unwrap();
+            assert_eq!(link.lines[1].content, "missing");
+        }
+    }
+}
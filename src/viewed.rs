@@ -0,0 +1,126 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    diff_file::DiffFile,
+    diff_source::DiffSource,
+    state::{fnv1a, state_file},
+};
+
+/// The files a reviewer has marked as viewed, persisted per repository and range.
+///
+/// Each mark records a hash of the file's diff, so it no longer applies once the changes to
+/// that file are modified.
+#[derive(Default)]
+pub struct ViewedFiles {
+    /// Where the marks are saved, or `None` when the source is not a repository range.
+    path: Option<PathBuf>,
+    marks: HashSet<(String, u64)>,
+    /// The mark of each loaded file by index, computed once per load rather than every frame.
+    file_marks: Vec<(String, u64)>,
+}
+
+impl ViewedFiles {
+    /// Loads the marks saved under `.git/diffscape/` for the range compared by `source`.
+    pub fn load(source: &DiffSource) -> Result<Self> {
+        let Some(path) = state_file(source, "viewed")? else {
+            return Ok(Self::default());
+        };
+
+        let marks = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().filter_map(parse_mark).collect(),
+            Err(_) => HashSet::new(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            marks,
+            file_marks: Vec::new(),
+        })
+    }
+
+    /// Hashes the diff of each file, which must be called whenever the file list changes.
+    pub fn set_files(&mut self, files: &[DiffFile]) {
+        self.file_marks = files.iter().map(mark).collect();
+    }
+
+    /// Returns whether the file at `index` of the list given to `set_files` is viewed.
+    pub fn is_viewed(&self, index: usize) -> bool {
+        self.file_marks
+            .get(index)
+            .is_some_and(|mark| self.marks.contains(mark))
+    }
+
+    /// Marks the file at `index` as viewed, or clears its mark, and saves the change.
+    pub fn toggle(&mut self, index: usize) -> Result<()> {
+        let Some(mark) = self.file_marks.get(index) else {
+            return Ok(());
+        };
+        if !self.marks.remove(mark) {
+            self.marks.insert(mark.clone());
+        }
+
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut marks: Vec<_> = self.marks.iter().collect();
+        marks.sort();
+        let text: String = marks
+            .into_iter()
+            .map(|(name, hash)| format!("{hash:016x}\t{name}\n"))
+            .collect();
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)
+            .with_context(|| format!("failed to save viewed files to {}", path.display()))
+    }
+}
+
+fn mark(file: &DiffFile) -> (String, u64) {
+    let mut diff = Vec::new();
+    for line in &file.lines {
+        diff.extend_from_slice(format!("{:?}\t{}\n", line.line_type, line.content).as_bytes());
+    }
+
+    (file.get_name().to_string(), fnv1a(&diff))
+}
+
+fn parse_mark(line: &str) -> Option<(String, u64)> {
+    let (hash, name) = line.split_once('\t')?;
+    Some((name.to_string(), u64::from_str_radix(hash, 16).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_file::DiffLine;
+
+    #[test]
+    fn test_mark_resets_when_diff_changes() {
+        let mut file = DiffFile::new("src/main.rs");
+        file.add_line(DiffLine::new_added("+one", 1));
+
+        let mut viewed = ViewedFiles::default();
+        viewed.set_files(std::slice::from_ref(&file));
+        viewed.toggle(0).unwrap();
+        assert!(viewed.is_viewed(0));
+
+        file.add_line(DiffLine::new_added("+two", 2));
+        viewed.set_files(std::slice::from_ref(&file));
+        assert!(!viewed.is_viewed(0));
+
+        let (name, hash) = mark(&file);
+        assert_eq!(
+            parse_mark(&format!("{hash:016x}\t{name}")),
+            Some((name, hash))
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use anyhow::Result;
+
+use crate::{git, state::fnv1a};
+
+/// Polls the working tree and index for changes.
+///
+/// Each check fingerprints `git status` along with the modification times of the index and of
+/// every file it lists, so saving an already modified file is noticed too.
+pub struct WorkTreeWatcher {
+    root: PathBuf,
+    index: PathBuf,
+    fingerprint: u64,
+}
+
+impl WorkTreeWatcher {
+    pub fn new() -> Result<Self> {
+        git::ensure_repository()?;
+
+        let root = PathBuf::from(git::run(&["rev-parse", "--show-toplevel"])?.trim());
+        let index = PathBuf::from(git::run(&["rev-parse", "--git-path", "index"])?.trim());
+        let mut watcher = Self {
+            root,
+            index,
+            fingerprint: 0,
+        };
+        watcher.fingerprint = watcher.fingerprint()?;
+
+        Ok(watcher)
+    }
+
+    /// Returns true when something changed since the last check.
+    pub fn changed(&mut self) -> bool {
+        // A failing check (such as during a rebase) is treated as no change
+        let Ok(fingerprint) = self.fingerprint() else {
+            return false;
+        };
+        let changed = fingerprint != self.fingerprint;
+        self.fingerprint = fingerprint;
+        changed
+    }
+
+    fn fingerprint(&self) -> Result<u64> {
+        // Without optional locks, git status doesn't refresh the index and set off the watcher
+        let status = git::run(&[
+            "--no-optional-locks",
+            "-C",
+            &self.root.to_string_lossy(),
+            "status",
+            "--porcelain",
+            "-z",
+            "--untracked-files=all",
+        ])?;
+
+        let mut state = status.clone();
+        let paths = status
+            .split('\0')
+            .filter_map(|entry| entry.get(3..))
+            .map(|path| self.root.join(path));
+        for path in paths.chain([self.index.clone()]) {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok());
+            state.push_str(&format!("{modified:?}\0"));
+        }
+
+        Ok(fnv1a(state.as_bytes()))
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=A U Thor",
+            "-c",
+            "user.email=author@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+fn diffscape_json(dir: &Path, args: &[&str]) -> serde_json::Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_diffscape"))
+        .arg("--json")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Runs `diffscape --json` the way scripts do, with nothing on stdin, in a repository with a
+/// modified and a renamed file.
+#[test]
+fn test_json_from_repository() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path();
+    git(repo, &["init", "-q"]);
+    fs::write(repo.join("notes.txt"), "one\ntwo\n").unwrap();
+    fs::write(repo.join("old.txt"), "a\nb\nc\nd\ne\n").unwrap();
+    git(repo, &["add", "."]);
+    git(repo, &["commit", "-q", "-m", "initial"]);
+
+    fs::write(repo.join("notes.txt"), "one\n2").unwrap();
+    git(repo, &["mv", "old.txt", "new.txt"]);
+    fs::write(repo.join("untracked.txt"), "new\n").unwrap();
+
+    // The working tree, like `git diff`
+    let json = diffscape_json(repo, &[]);
+    let files = json["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1, "only the unstaged change is listed: {json}");
+    assert_eq!(files[0]["path"], "notes.txt");
+
+    let json = diffscape_json(repo, &["HEAD"]);
+    let files = json["files"].as_array().unwrap();
+    assert_eq!(files.len(), 2, "untracked files are left out: {json}");
+
+    let renamed = &files[0];
+    assert_eq!(renamed["path"], "new.txt");
+    assert_eq!(renamed["old_path"], "old.txt");
+    assert_eq!(renamed["status"], "R");
+
+    let notes = &files[1];
+    assert_eq!(notes["path"], "notes.txt");
+    assert_eq!(notes["old_path"], serde_json::Value::Null);
+    let lines = notes["hunks"][0]["lines"].as_array().unwrap();
+    assert_eq!(lines[1]["type"], "removed");
+    assert_eq!(lines[1]["old_line"], 2);
+    assert_eq!(lines[2]["content"], "2");
+    assert_eq!(lines[2]["no_newline"], true);
+    assert_eq!(
+        lines[2]["changes"],
+        serde_json::json!([{ "start": 0, "end": 1 }])
+    );
+}